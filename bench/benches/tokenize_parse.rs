@@ -0,0 +1,109 @@
+//! Throughput of `tokenize` and `parse` on generated queries of increasing size,
+//! so that super-linear behaviour shows up as the inputs grow.
+//!
+//! Run from the `bench` directory:
+//!
+//! ```text
+//! cargo bench
+//! ```
+//!
+//! Criterion keeps the previous run under `target/criterion` and reports the
+//! change against it, which is the baseline for later optimizations.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+// Cargo builds benchmarks with `cfg(test)` but without the test harness, so the
+// modules' unit tests are compiled with their `#[test]` functions stripped.
+#[allow(dead_code, unused_imports)]
+#[path = "../../expression.rs"]
+mod expression;
+#[allow(dead_code, unused_imports)]
+#[path = "../../parser.rs"]
+mod parser;
+#[allow(dead_code, unused_imports)]
+#[path = "../../tokenizer.rs"]
+mod tokenizer;
+
+const SIZES: [usize; 3] = [10, 50, 200];
+
+// `SELECT c0, c1, ... FROM t`
+fn wide_select(columns: usize) -> String {
+    let list: Vec<String> = (0..columns).map(|i| format!("c{}", i)).collect();
+    format!("SELECT {} FROM t", list.join(", "))
+}
+
+// `SELECT order_id, customer_id, order_id, ... FROM orders`, a wide query that
+// names the same few columns over and over, where interned identifiers share
+// one allocation per name instead of one per occurrence.
+fn repeated_columns(columns: usize) -> String {
+    let names = ["order_id", "customer_id", "created_at", "total_amount"];
+    let list: Vec<&str> = (0..columns).map(|i| names[i % names.len()]).collect();
+    format!("SELECT {} FROM orders", list.join(", "))
+}
+
+// `SELECT (c0 + 1), ((c1 + 1)), ... FROM t`
+fn parenthesized_columns(columns: usize) -> String {
+    let list: Vec<String> = (0..columns)
+        .map(|i| format!("{}c{} + 1{}", "(".repeat(i % 4 + 1), i, ")".repeat(i % 4 + 1)))
+        .collect();
+    format!("SELECT {} FROM t", list.join(", "))
+}
+
+// `select customer_name_0 from t where customer_name_0 is not null and ...`, a
+// lowercase mix of identifiers and keywords that exercises keyword lookup.
+fn mixed_identifiers(terms: usize) -> String {
+    let conditions: Vec<String> = (0..terms).map(|i| format!("customer_name_{} is not null", i)).collect();
+    format!("select customer_name_0 from t where {}", conditions.join(" and "))
+}
+
+// `SELECT a FROM t WHERE ((((a = 1) OR b = 2) OR b = 2) ...)`
+fn nested_where(depth: usize) -> String {
+    let mut condition = "a = 1".to_string();
+    for _ in 0..depth {
+        condition = format!("({}) OR b = 2", condition);
+    }
+    format!("SELECT a FROM t WHERE {}", condition)
+}
+
+// `SELECT a FROM t WHERE a + a + a ...`
+fn addition_chain(terms: usize) -> String {
+    format!("SELECT a FROM t WHERE {}", vec!["a"; terms].join(" + "))
+}
+
+// `SELECT a FROM t WHERE a IN (0, 1, 2, ...)`, standing in for a large
+// multi-row INSERT until the parser supports one.
+fn long_in_list(items: usize) -> String {
+    let list: Vec<String> = (0..items).map(|i| i.to_string()).collect();
+    format!("SELECT a FROM t WHERE a IN ({})", list.join(", "))
+}
+
+fn bench_queries(c: &mut Criterion, name: &str, generate: fn(usize) -> String) {
+    let mut group = c.benchmark_group(name);
+    for size in SIZES {
+        let sql = generate(size);
+        let tokens = tokenizer::tokenize(&sql);
+        assert!(parser::parse(&tokens).is_ok(), "benchmark query must parse: {}", sql);
+
+        group.throughput(Throughput::Bytes(sql.len() as u64));
+        group.bench_with_input(BenchmarkId::new("tokenize", size), &sql, |b, sql| {
+            b.iter(|| tokenizer::tokenize(black_box(sql)))
+        });
+        group.bench_with_input(BenchmarkId::new("parse", size), &tokens, |b, tokens| {
+            b.iter(|| parser::parse(black_box(tokens)))
+        });
+    }
+    group.finish();
+}
+
+fn benchmarks(c: &mut Criterion) {
+    bench_queries(c, "wide_select", wide_select);
+    bench_queries(c, "repeated_columns", repeated_columns);
+    bench_queries(c, "parenthesized_columns", parenthesized_columns);
+    bench_queries(c, "mixed_identifiers", mixed_identifiers);
+    bench_queries(c, "nested_where", nested_where);
+    bench_queries(c, "addition_chain", addition_chain);
+    bench_queries(c, "long_in_list", long_in_list);
+}
+
+criterion_group!(benches, benchmarks);
+criterion_main!(benches);
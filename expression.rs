@@ -1,145 +1,2200 @@
-use crate::tokenizer::Token;
-
-#[derive(Debug, Clone)]
-pub enum Expression {
-    Number(i64),
-    Identifier(String),
-    String(String),
-    Bool(bool),
-    UnaryOp {
-        op: UnaryOperator,
-        expr: Box<Expression>,
-    },
-    BinaryOp {
-        left: Box<Expression>,
-        op: BinaryOperator,
-        right: Box<Expression>,
-    },
-}
-
-#[derive(Debug, Clone)]
-pub enum UnaryOperator {
-    Not,
-    Asc,
-    Desc,
-}
-
-#[derive(Debug, Clone)]
-pub enum BinaryOperator {
-    Or,
-    And,
-    Equal,
-    NotEqual,
-    Less,
-    LessEqual,
-    Greater,
-    GreaterEqual,
-    Add,
-    Subtract,
-    Multiply,
-    Divide,
-    Unknown(String), // fallback
-}
-
-pub fn parse_expression(tokens: &[Token], min_prec: u8) -> Result<(Expression, usize), String> {
-    let mut pos = 0;
-
-    let mut lhs = match tokens.get(pos) {
-        Some(Token::Number(n)) => {
-            pos += 1;
-            Expression::Number(*n)
-        }
-        Some(Token::StringLiteral(s)) => {
-            pos += 1;
-            Expression::String(s.clone())
-        }
-        Some(Token::BoolLiteral(b)) => {
-            pos += 1;
-            Expression::Bool(*b)
-        }
-        Some(Token::Identifier(name)) => {
-            pos += 1;
-            Expression::Identifier(name.clone())
-        }
-        Some(Token::Keyword(k)) if k == "NOT" => {
-            pos += 1;
-            let (inner_expr, consumed) = parse_expression(&tokens[pos..], 6)?; // 6 = higher than any binary op
-            pos += consumed;
-            Expression::UnaryOp {
-                op: UnaryOperator::Not,
-                expr: Box::new(inner_expr),
-            }
-        }
-        Some(Token::LParen) => {
-            pos += 1;
-            let (expr, consumed) = parse_expression(&tokens[pos..], 0)?;
-            pos += consumed;
-            match tokens.get(pos) {
-                Some(Token::RParen) => {
-                    pos += 1;
-                    expr
-                }
-                _ => return Err("Expected ')'".to_string()),
-            }
-        }
-        _ => return Err("Unexpected token at beginning of expression".to_string()),
-    };
-
-    loop {
-        let op_token = match tokens.get(pos) {
-            Some(Token::Operator(op)) => op.clone(),
-            _ => break,
-        };
-
-        let prec = get_precedence(&op_token);
-        if prec < min_prec {
-            break;
-        }
-
-        let binary_op = match to_binary_operator(&op_token) {
-            Some(op) => op,
-            None => return Err(format!("Unknown operator '{}'", op_token)),
-        };
-
-        pos += 1;
-        let (rhs, consumed) = parse_expression(&tokens[pos..], prec + 1)?;
-        pos += consumed;
-
-        lhs = Expression::BinaryOp {
-            left: Box::new(lhs),
-            op: binary_op,
-            right: Box::new(rhs),
-        };
-    }
-
-    Ok((lhs, pos))
-}
-
-fn get_precedence(op: &str) -> u8 {
-    match op {
-        "OR" => 1,
-        "AND" => 2,
-        "=" | "<" | ">" | "<=" | ">=" => 3,
-        "+" | "-" => 4,
-        "*" | "/" => 5,
-        _ => 0,
-    }
-}
-
-fn to_binary_operator(op: &str) -> Option<BinaryOperator> {
-    match op {
-        "OR" => Some(BinaryOperator::Or),
-        "AND" => Some(BinaryOperator::And),
-        "=" => Some(BinaryOperator::Equal),
-        "!=" => Some(BinaryOperator::NotEqual),
-        "<" => Some(BinaryOperator::Less),
-        "<=" => Some(BinaryOperator::LessEqual),
-        ">" => Some(BinaryOperator::Greater),
-        ">=" => Some(BinaryOperator::GreaterEqual),
-        "+" => Some(BinaryOperator::Add),
-        "-" => Some(BinaryOperator::Subtract),
-        "*" => Some(BinaryOperator::Multiply),
-        "/" => Some(BinaryOperator::Divide),
-        other => Some(BinaryOperator::Unknown(other.to_string())),
-    }
-}
\ No newline at end of file
+use std::fmt;
+
+use crate::parser::{parse_subquery, ParseError, ParserOptions, Statement};
+use crate::tokenizer::{Keyword, Name, PlaceholderKind, Symbol, Token};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Number(i64),
+    Identifier(Symbol),
+    /// A dotted system variable such as `@@global.max_connections`, split into its parts.
+    QualifiedName(Vec<String>),
+    String(String),
+    Bool(bool),
+    /// SQL's `UNKNOWN`: the null value of the boolean type.
+    Unknown,
+    Placeholder(PlaceholderKind),
+    Subquery(Box<Statement>),
+    /// `name(args)`. `distinct` is set for aggregates like `COUNT(DISTINCT x)`.
+    Function {
+        name: String,
+        args: Vec<Expression>,
+        distinct: bool,
+    },
+    /// The `*` argument of `COUNT(*)`.
+    Wildcard,
+    /// `t.*` in a SELECT list: every column of table or alias `t`.
+    QualifiedStar(String),
+    /// `expr [AS] alias` in a SELECT list.
+    Aliased {
+        expr: Box<Expression>,
+        alias: String,
+    },
+    /// `left op ANY | SOME | ALL (SELECT ...)`.
+    QuantifiedComparison {
+        left: Box<Expression>,
+        op: BinaryOperator,
+        quantifier: AnyOrAll,
+        subquery: Box<Statement>,
+    },
+    /// `[NOT] EXISTS (SELECT ...)`. A leading NOT is folded into `negated` rather
+    /// than wrapping the predicate in a unary NOT.
+    Exists {
+        subquery: Box<Statement>,
+        negated: bool,
+    },
+    InList {
+        expr: Box<Expression>,
+        list: Vec<Expression>,
+        negated: bool,
+    },
+    InSubquery {
+        expr: Box<Expression>,
+        subquery: Box<Statement>,
+        negated: bool,
+    },
+    /// `expr [NOT] BETWEEN low AND high`, with both bounds inclusive.
+    Between {
+        expr: Box<Expression>,
+        low: Box<Expression>,
+        high: Box<Expression>,
+        negated: bool,
+    },
+    /// `expr [NOT] LIKE pattern [ESCAPE escape]`; ILIKE sets `case_insensitive`.
+    Like {
+        expr: Box<Expression>,
+        pattern: Box<Expression>,
+        negated: bool,
+        escape: Option<Box<Expression>>,
+        case_insensitive: bool,
+    },
+    /// `CASE [operand] WHEN .. THEN .. [...] [ELSE ..] END`. With an operand each
+    /// WHEN holds a value compared against it; without one, a condition.
+    Case {
+        operand: Option<Box<Expression>>,
+        branches: Vec<(Expression, Expression)>,
+        else_result: Option<Box<Expression>>,
+    },
+    /// `expr IS [NOT] NULL | TRUE | FALSE | UNKNOWN`, which unlike `=` never yields NULL.
+    IsPredicate {
+        expr: Box<Expression>,
+        negated: bool,
+        target: IsTarget,
+    },
+    /// `TRIM([LEADING | TRAILING | BOTH] [chars] FROM expr)` or `TRIM(expr)`.
+    /// Without `chars`, spaces are removed.
+    Trim {
+        side: TrimSide,
+        chars: Option<Box<Expression>>,
+        expr: Box<Expression>,
+    },
+    /// `SUBSTRING(expr [FROM start] [FOR length])`, the keyword form of
+    /// `SUBSTRING(expr, start, length)`, which parses as a function call.
+    Substring {
+        expr: Box<Expression>,
+        from: Option<Box<Expression>>,
+        for_: Option<Box<Expression>>,
+    },
+    /// `OVERLAY(string PLACING replacement FROM start [FOR length])`: `string`
+    /// with `length` characters from `start` replaced, by default as many as
+    /// `replacement` has.
+    Overlay {
+        string: Box<Expression>,
+        replacement: Box<Expression>,
+        from: Box<Expression>,
+        for_: Option<Box<Expression>>,
+    },
+    /// `POSITION(needle IN haystack)`: the 1-based index of `needle` in
+    /// `haystack`, or 0 if it does not occur.
+    Position {
+        needle: Box<Expression>,
+        haystack: Box<Expression>,
+    },
+    /// `expr AT TIME ZONE zone`: a timestamp converted to another time zone.
+    AtTimeZone {
+        expr: Box<Expression>,
+        zone: Box<Expression>,
+    },
+    UnaryOp {
+        op: UnaryOperator,
+        expr: Box<Expression>,
+    },
+    BinaryOp {
+        left: Box<Expression>,
+        op: BinaryOperator,
+        right: Box<Expression>,
+    },
+}
+
+/// What an `IS` predicate tests for. `IS UNKNOWN` agrees with `IS NULL` on every
+/// input but is kept as written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IsTarget {
+    Null,
+    True,
+    False,
+    Unknown,
+}
+
+/// The end of the string TRIM removes characters from. BOTH is the default.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrimSide {
+    Leading,
+    Trailing,
+    Both,
+}
+
+/// SOME is a synonym for ANY and parses as `Any`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnyOrAll {
+    Any,
+    All,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnaryOperator {
+    Not,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinaryOperator {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    /// `^`, which binds tighter than `*` and groups to the right.
+    Power,
+    IsDistinctFrom,
+    IsNotDistinctFrom,
+    /// `SIMILAR TO`, a LIKE pattern extended with regular expression syntax.
+    SimilarTo,
+    NotSimilarTo,
+    /// The POSIX regex matches `~` and `~*`, and their negations `!~` and `!~*`.
+    RegexMatch,
+    RegexMatchCI,
+    RegexNotMatch,
+    RegexNotMatchCI,
+    Unknown(String), // fallback
+}
+
+// Bounds the recursion of nested parentheses and NOT chains so adversarial input
+// produces an error instead of overflowing the stack.
+pub(crate) const MAX_EXPRESSION_DEPTH: usize = 256;
+
+pub fn parse_expression(tokens: &[Token], min_prec: u8) -> Result<(Expression, usize), ParseError> {
+    parse_expression_with_options(tokens, min_prec, &ParserOptions::default())
+}
+
+/// Like [`parse_expression`], but subqueries in the expression are parsed with
+/// `options`.
+pub fn parse_expression_with_options(
+    tokens: &[Token],
+    min_prec: u8,
+    options: &ParserOptions,
+) -> Result<(Expression, usize), ParseError> {
+    parse_expression_at_depth(tokens, min_prec, options, 0)
+}
+
+pub(crate) fn parse_expression_at_depth(tokens: &[Token], min_prec: u8, options: &ParserOptions, depth: usize) -> Result<(Expression, usize), ParseError> {
+    if depth > MAX_EXPRESSION_DEPTH {
+        return Err(ParseError::InvalidExpression {
+            reason: "Expression is nested too deeply".to_string(),
+            pos: 0,
+        });
+    }
+    let (lhs, pos) = parse_primary(tokens, options, depth)?;
+    parse_operators(tokens, lhs, pos, min_prec, options, depth)
+}
+
+// Continues an expression whose leftmost operand `lhs` ends at `pos` with the
+// operators after it that bind at least as tightly as `min_prec`, returning the
+// expression and the index just past it.
+fn parse_operators(
+    tokens: &[Token],
+    mut lhs: Expression,
+    mut pos: usize,
+    min_prec: u8,
+    options: &ParserOptions,
+    depth: usize,
+) -> Result<(Expression, usize), ParseError> {
+    // Left operands still waiting for their right-hand side, each with its operator
+    // and precedence. Binary operators are resolved against this stack instead of
+    // by recursion, so long operator chains use constant stack space.
+    let mut pending: Vec<(Expression, BinaryOperator, u8)> = Vec::new();
+
+    loop {
+        // Keyword predicates that may be negated by a leading NOT: `a [NOT] IN (...)`,
+        // `a [NOT] BETWEEN x AND y`,
+        // `a [NOT] LIKE 'x%'`, `a [NOT] SIMILAR TO 'x%'`.
+        let negated = matches!(tokens.get(pos), Some(Token::Keyword(Keyword::Not)));
+        let predicate_pos = if negated { pos + 1 } else { pos };
+        if let Some(Token::Keyword(k)) = tokens.get(predicate_pos) {
+            if *k == Keyword::In {
+                if COMPARISON_PRECEDENCE < min_prec {
+                    break;
+                }
+                lhs = reduce_pending(&mut pending, lhs, COMPARISON_PRECEDENCE);
+                pos = predicate_pos + 1;
+                let (predicate, consumed) = parse_in_predicate(&tokens[pos..], lhs, negated, options, depth).map_err(|e| e.offset(pos))?;
+                pos += consumed;
+                lhs = predicate;
+                continue;
+            }
+            if *k == Keyword::Between {
+                if COMPARISON_PRECEDENCE < min_prec {
+                    break;
+                }
+                lhs = reduce_pending(&mut pending, lhs, COMPARISON_PRECEDENCE);
+                pos = predicate_pos + 1;
+                // The bounds bind tighter than AND, so the first AND after the low
+                // bound belongs to BETWEEN and a later one is a conjunction:
+                // `a BETWEEN 1 AND 10 AND b = 2` is `(a BETWEEN 1 AND 10) AND b = 2`.
+                let (low, consumed) = parse_expression_at_depth(&tokens[pos..], COMPARISON_PRECEDENCE + 1, options, depth + 1).map_err(|e| e.offset(pos))?;
+                pos += consumed;
+                match tokens.get(pos) {
+                    Some(Token::Operator(op)) if op == "AND" => pos += 1,
+                    _ => return Err(expected_at(tokens, pos, "AND in BETWEEN")),
+                }
+                let (high, consumed) = parse_expression_at_depth(&tokens[pos..], COMPARISON_PRECEDENCE + 1, options, depth + 1).map_err(|e| e.offset(pos))?;
+                pos += consumed;
+                lhs = Expression::Between {
+                    expr: Box::new(lhs),
+                    low: Box::new(low),
+                    high: Box::new(high),
+                    negated,
+                };
+                continue;
+            }
+            if *k == Keyword::Like || *k == Keyword::Ilike {
+                if COMPARISON_PRECEDENCE < min_prec {
+                    break;
+                }
+                lhs = reduce_pending(&mut pending, lhs, COMPARISON_PRECEDENCE);
+                let case_insensitive = *k == Keyword::Ilike;
+                pos = predicate_pos + 1;
+                if matches!(tokens.get(pos), None | Some(Token::Eof)) {
+                    return Err(expected_at(tokens, pos, &format!("pattern after {}", k)));
+                }
+                let (pattern, consumed) = parse_expression_at_depth(&tokens[pos..], COMPARISON_PRECEDENCE + 1, options, depth + 1).map_err(|e| e.offset(pos))?;
+                pos += consumed;
+                let escape = match tokens.get(pos) {
+                    Some(Token::Keyword(Keyword::Escape)) => {
+                        pos += 1;
+                        let (escape, consumed) =
+                            parse_expression_at_depth(&tokens[pos..], COMPARISON_PRECEDENCE + 1, options, depth + 1).map_err(|e| e.offset(pos))?;
+                        pos += consumed;
+                        Some(Box::new(escape))
+                    }
+                    _ => None,
+                };
+                lhs = Expression::Like {
+                    expr: Box::new(lhs),
+                    pattern: Box::new(pattern),
+                    negated,
+                    escape,
+                    case_insensitive,
+                };
+                continue;
+            }
+            if *k == Keyword::Similar {
+                if COMPARISON_PRECEDENCE < min_prec {
+                    break;
+                }
+                if !matches!(tokens.get(predicate_pos + 1), Some(Token::Keyword(Keyword::To))) {
+                    return Err(expected_at(tokens, predicate_pos + 1, "TO after SIMILAR"));
+                }
+                lhs = reduce_pending(&mut pending, lhs, COMPARISON_PRECEDENCE);
+                let op = if negated {
+                    BinaryOperator::NotSimilarTo
+                } else {
+                    BinaryOperator::SimilarTo
+                };
+                pos = predicate_pos + 2;
+                pending.push((lhs, op, COMPARISON_PRECEDENCE));
+                let (operand, consumed) = parse_primary(&tokens[pos..], options, depth).map_err(|e| e.offset(pos))?;
+                pos += consumed;
+                lhs = operand;
+                continue;
+            }
+        }
+
+        // `AT TIME ZONE` binds tighter than any binary operator, so it applies to
+        // the operand right before it: `a + b AT TIME ZONE 'UTC'` converts `b`.
+        // None of the three words is reserved, so each is matched by name.
+        let word_at = |offset: usize, word: &str| {
+            matches!(tokens.get(pos + offset), Some(Token::Identifier(name)) if name.eq_ignore_ascii_case(word))
+        };
+        if word_at(0, "AT") && word_at(1, "TIME") && word_at(2, "ZONE") {
+            if AT_TIME_ZONE_PRECEDENCE < min_prec {
+                break;
+            }
+            pos += 3;
+            let (zone, consumed) = parse_primary(&tokens[pos..], options, depth).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            lhs = Expression::AtTimeZone {
+                expr: Box::new(lhs),
+                zone: Box::new(zone),
+            };
+            continue;
+        }
+
+        // Postfix `IS [NOT] NULL | TRUE | FALSE`, and `IS [NOT] DISTINCT FROM`, a
+        // null-safe comparison spelled with keywords. The NOT here belongs to IS
+        // and is never parsed as the unary operator.
+        if let Some(Token::Keyword(k)) = tokens.get(pos) {
+            if *k == Keyword::Is {
+                if COMPARISON_PRECEDENCE < min_prec {
+                    break;
+                }
+                lhs = reduce_pending(&mut pending, lhs, COMPARISON_PRECEDENCE);
+                let negated = matches!(tokens.get(pos + 1), Some(Token::Keyword(Keyword::Not)));
+                let next = if negated { pos + 2 } else { pos + 1 };
+                let target = match tokens.get(next) {
+                    Some(Token::Keyword(Keyword::Null)) => Some(IsTarget::Null),
+                    Some(Token::Keyword(Keyword::Unknown)) => Some(IsTarget::Unknown),
+                    Some(Token::BoolLiteral(true)) => Some(IsTarget::True),
+                    Some(Token::BoolLiteral(false)) => Some(IsTarget::False),
+                    _ => None,
+                };
+                if let Some(target) = target {
+                    pos = next + 1;
+                    lhs = Expression::IsPredicate {
+                        expr: Box::new(lhs),
+                        negated,
+                        target,
+                    };
+                    continue;
+                }
+                let op = match (tokens.get(next), tokens.get(next + 1)) {
+                    (Some(Token::Keyword(Keyword::Distinct)), Some(Token::Keyword(Keyword::From))) => {
+                        if negated {
+                            BinaryOperator::IsNotDistinctFrom
+                        } else {
+                            BinaryOperator::IsDistinctFrom
+                        }
+                    }
+                    _ => return Err(expected_at(tokens, next, "NULL, TRUE, FALSE, UNKNOWN or DISTINCT FROM after IS")),
+                };
+                pos = next + 2;
+                pending.push((lhs, op, COMPARISON_PRECEDENCE));
+                let (operand, consumed) = parse_primary(&tokens[pos..], options, depth).map_err(|e| e.offset(pos))?;
+                pos += consumed;
+                lhs = operand;
+                continue;
+            }
+        }
+
+        let op_token = match tokens.get(pos) {
+            Some(Token::Operator(op)) => op.clone(),
+            // `*` has its own token because it is also the SELECT wildcard.
+            Some(Token::Star) => "*".to_string(),
+            _ => break,
+        };
+
+        let prec = get_precedence(&op_token);
+        if prec < min_prec {
+            break;
+        }
+
+        let binary_op = match to_binary_operator(&op_token) {
+            Some(op) => op,
+            None => {
+                return Err(ParseError::InvalidExpression {
+                    reason: format!("Unknown operator '{}'", op_token),
+                    pos,
+                })
+            }
+        };
+
+        // Everything pending that binds at least as tightly is complete, which
+        // keeps equal-precedence chains left-associative. A right-associative
+        // operator leaves pending operators of its own precedence waiting, so
+        // they take the rest of the chain as their right operand.
+        let reduce_from = match associativity(&binary_op) {
+            Assoc::Left => prec,
+            Assoc::Right => prec + 1,
+        };
+        lhs = reduce_pending(&mut pending, lhs, reduce_from);
+        pos += 1;
+
+        if prec == COMPARISON_PRECEDENCE {
+            if let Some(quantifier) = quantifier_at(tokens, pos) {
+                let (comparison, consumed) =
+                    parse_quantified_comparison(&tokens[pos..], lhs, binary_op, quantifier, options, depth).map_err(|e| e.offset(pos))?;
+                pos += consumed;
+                lhs = comparison;
+                continue;
+            }
+        }
+
+        pending.push((lhs, binary_op, prec));
+        let (operand, consumed) = parse_primary(&tokens[pos..], options, depth).map_err(|e| e.offset(pos))?;
+        pos += consumed;
+        lhs = operand;
+    }
+
+    Ok((reduce_pending(&mut pending, lhs, 0), pos))
+}
+
+// Pops pending left operands whose operator binds at least as tightly as
+// `min_prec`, combining each with the expression to its right.
+fn reduce_pending(
+    pending: &mut Vec<(Expression, BinaryOperator, u8)>,
+    mut rhs: Expression,
+    min_prec: u8,
+) -> Expression {
+    while let Some((_, _, prec)) = pending.last() {
+        if *prec < min_prec {
+            break;
+        }
+        let (left, op, _) = pending.pop().expect("checked by last()");
+        rhs = Expression::BinaryOp {
+            left: Box::new(left),
+            op,
+            right: Box::new(rhs),
+        };
+    }
+    rhs
+}
+
+// Parses a single operand: a literal, name, function call, CASE, EXISTS, a NOT
+// applied to an operand, or a parenthesized expression or subquery. Returns the
+// operand and the number of tokens consumed.
+fn parse_primary(tokens: &[Token], options: &ParserOptions, depth: usize) -> Result<(Expression, usize), ParseError> {
+    let mut pos = 0;
+
+    let lhs = match tokens.get(pos) {
+        Some(Token::Number(n)) => {
+            pos += 1;
+            Expression::Number(*n)
+        }
+        Some(Token::StringLiteral(s)) => {
+            pos += 1;
+            Expression::String(s.clone())
+        }
+        Some(Token::BoolLiteral(b)) => {
+            pos += 1;
+            Expression::Bool(*b)
+        }
+        Some(Token::Identifier(name)) if name.starts_with("@@") && name.contains('.') => {
+            pos += 1;
+            Expression::QualifiedName(name.split('.').map(str::to_string).collect())
+        }
+        // TRIM separates its arguments with keywords instead of commas.
+        Some(Token::Identifier(name)) if name.eq_ignore_ascii_case("TRIM") && tokens.get(pos + 1) == Some(&Token::LParen) => {
+            pos += 2;
+            let (trim, consumed) = parse_trim(&tokens[pos..], options, depth).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            trim
+        }
+        Some(Token::Identifier(name))
+            if name.eq_ignore_ascii_case("SUBSTRING") && tokens.get(pos + 1) == Some(&Token::LParen) =>
+        {
+            pos += 2;
+            let (substring, consumed) = parse_substring(&tokens[pos..], name, options, depth).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            substring
+        }
+        Some(Token::Identifier(name))
+            if name.eq_ignore_ascii_case("OVERLAY") && tokens.get(pos + 1) == Some(&Token::LParen) =>
+        {
+            pos += 2;
+            let (overlay, consumed) = parse_overlay(&tokens[pos..], name, options, depth).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            overlay
+        }
+        Some(Token::Identifier(name))
+            if name.eq_ignore_ascii_case("POSITION") && tokens.get(pos + 1) == Some(&Token::LParen) =>
+        {
+            pos += 2;
+            let (position, consumed) = parse_position(&tokens[pos..], name, options, depth).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            position
+        }
+        Some(Token::Identifier(name)) if tokens.get(pos + 1) == Some(&Token::LParen) => {
+            pos += 2;
+            let (function, consumed) = parse_function_call(&tokens[pos..], name, options, depth).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            function
+        }
+        Some(Token::Identifier(name)) => {
+            pos += 1;
+            Expression::Identifier(name.clone())
+        }
+        Some(Token::Keyword(Keyword::Unknown)) => {
+            pos += 1;
+            Expression::Unknown
+        }
+        Some(Token::Placeholder(kind)) => {
+            pos += 1;
+            Expression::Placeholder(kind.clone())
+        }
+        Some(Token::Keyword(Keyword::Exists)) => {
+            pos += 1;
+            let (exists, consumed) = parse_exists(&tokens[pos..], false, options, depth).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            exists
+        }
+        Some(Token::Keyword(Keyword::Not)) if matches!(tokens.get(pos + 1), Some(Token::Keyword(Keyword::Exists))) => {
+            pos += 2;
+            let (exists, consumed) = parse_exists(&tokens[pos..], true, options, depth).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            exists
+        }
+        Some(Token::Keyword(Keyword::Not)) => {
+            pos += 1;
+            let (inner_expr, consumed) = parse_expression_at_depth(&tokens[pos..], 7, options, depth + 1).map_err(|e| e.offset(pos))?; // 7 = higher than any binary op
+            pos += consumed;
+            Expression::UnaryOp {
+                op: UnaryOperator::Not,
+                expr: Box::new(inner_expr),
+            }
+        }
+        Some(Token::Keyword(Keyword::Case)) => {
+            pos += 1;
+            let (case, consumed) = parse_case(&tokens[pos..], options, depth).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            case
+        }
+        Some(Token::LParen) if matches!(tokens.get(pos + 1), Some(Token::Keyword(Keyword::Select))) => {
+            pos += 1;
+            let (query, consumed) = parse_subquery(&tokens[pos..], options, depth).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            match tokens.get(pos) {
+                Some(Token::RParen) => {
+                    pos += 1;
+                    Expression::Subquery(Box::new(query))
+                }
+                _ => return Err(expected_at(tokens, pos, "')' after subquery")),
+            }
+        }
+        Some(Token::LParen) => {
+            pos += 1;
+            let (expr, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            match tokens.get(pos) {
+                Some(Token::RParen) => {
+                    pos += 1;
+                    expr
+                }
+                _ => return Err(expected_at(tokens, pos, "')'")),
+            }
+        }
+        Some(token) => return Err(ParseError::expected_name(token, pos, "an expression")),
+        None => return Err(ParseError::eof("an expression")),
+    };
+
+    Ok((lhs, pos))
+}
+
+// The error for `tokens[pos]` not being `expected`, or for the input ending there.
+fn expected_at(tokens: &[Token], pos: usize, expected: &str) -> ParseError {
+    match tokens.get(pos) {
+        Some(token) => ParseError::unexpected(token, pos, expected),
+        None => ParseError::eof(expected),
+    }
+}
+
+// Recognizes a quantifier right after a comparison operator. ALL, ANY and SOME
+// are ordinary identifiers everywhere else, so they only count as quantifiers
+// when a parenthesis follows.
+fn quantifier_at(tokens: &[Token], pos: usize) -> Option<AnyOrAll> {
+    match tokens.get(pos) {
+        Some(Token::Identifier(name)) if tokens.get(pos + 1) == Some(&Token::LParen) => {
+            if name.eq_ignore_ascii_case("ALL") {
+                Some(AnyOrAll::All)
+            } else if name.eq_ignore_ascii_case("ANY") || name.eq_ignore_ascii_case("SOME") {
+                Some(AnyOrAll::Any)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+// Parses `quantifier (SELECT ...)` after a comparison operator, returning the
+// comparison and the number of tokens consumed.
+fn parse_quantified_comparison(
+    tokens: &[Token],
+    left: Expression,
+    op: BinaryOperator,
+    quantifier: AnyOrAll,
+    options: &ParserOptions,
+    depth: usize,
+) -> Result<(Expression, usize), ParseError> {
+    let keyword = &tokens[0];
+    match (tokens.get(1), tokens.get(2)) {
+        (Some(Token::LParen), Some(Token::Keyword(Keyword::Select))) => {}
+        _ => return Err(expected_at(tokens, 1, &format!("'(SELECT ...)' after {}", keyword))),
+    }
+    let (subquery, consumed) = parse_subquery(&tokens[2..], options, depth).map_err(|e| e.offset(2))?;
+    let pos = 2 + consumed;
+    if tokens.get(pos) != Some(&Token::RParen) {
+        return Err(expected_at(tokens, pos, &format!("')' after {} subquery", keyword)));
+    }
+    Ok((
+        Expression::QuantifiedComparison {
+            left: Box::new(left),
+            op,
+            quantifier,
+            subquery: Box::new(subquery),
+        },
+        pos + 1,
+    ))
+}
+
+// Parses the parenthesized subquery after EXISTS, returning the predicate and the
+// number of tokens consumed.
+fn parse_exists(tokens: &[Token], negated: bool, options: &ParserOptions, depth: usize) -> Result<(Expression, usize), ParseError> {
+    match (tokens.first(), tokens.get(1)) {
+        (Some(Token::LParen), Some(Token::Keyword(Keyword::Select))) => {}
+        _ => return Err(expected_at(tokens, 0, "'(SELECT ...)' after EXISTS")),
+    }
+    let (subquery, consumed) = parse_subquery(&tokens[1..], options, depth).map_err(|e| e.offset(1))?;
+    let pos = 1 + consumed;
+    if tokens.get(pos) != Some(&Token::RParen) {
+        return Err(expected_at(tokens, pos, "')' after EXISTS subquery"));
+    }
+    Ok((
+        Expression::Exists {
+            subquery: Box::new(subquery),
+            negated,
+        },
+        pos + 1,
+    ))
+}
+
+// Parses the parenthesized list or subquery after `IN`, returning the predicate
+// and the number of tokens consumed.
+fn parse_in_predicate(
+    tokens: &[Token],
+    expr: Expression,
+    negated: bool,
+    options: &ParserOptions,
+    depth: usize,
+) -> Result<(Expression, usize), ParseError> {
+    if tokens.first() != Some(&Token::LParen) {
+        return Err(expected_at(tokens, 0, "'(' after IN"));
+    }
+    let mut pos = 1;
+
+    if let Some(Token::Keyword(k)) = tokens.get(pos) {
+        if *k == Keyword::Select {
+            let (subquery, consumed) = parse_subquery(&tokens[pos..], options, depth).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            if tokens.get(pos) != Some(&Token::RParen) {
+                return Err(expected_at(tokens, pos, "')' after IN subquery"));
+            }
+            return Ok((
+                Expression::InSubquery {
+                    expr: Box::new(expr),
+                    subquery: Box::new(subquery),
+                    negated,
+                },
+                pos + 1,
+            ));
+        }
+    }
+
+    if tokens.get(pos) == Some(&Token::RParen) {
+        return Err(ParseError::InvalidExpression {
+            reason: "IN list must not be empty".to_string(),
+            pos,
+        });
+    }
+    let mut list = Vec::new();
+    loop {
+        let (item, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+        pos += consumed;
+        list.push(item);
+        match tokens.get(pos) {
+            Some(Token::Comma) => pos += 1,
+            Some(Token::RParen) => break,
+            _ => return Err(expected_at(tokens, pos, "',' or ')' in IN list")),
+        }
+    }
+    Ok((
+        Expression::InList {
+            expr: Box::new(expr),
+            list,
+            negated,
+        },
+        pos + 1,
+    ))
+}
+
+// Whether `tokens` start with ALL as an aggregate's set quantifier rather than
+// as a column named `all`: the argument expression must follow it.
+fn is_all_quantifier(tokens: &[Token]) -> bool {
+    match (tokens.first(), tokens.get(1)) {
+        (Some(Token::Identifier(name)), Some(next)) if name.eq_ignore_ascii_case("ALL") => matches!(
+            next,
+            Token::Identifier(_)
+                | Token::Number(_)
+                | Token::StringLiteral(_)
+                | Token::BoolLiteral(_)
+                | Token::Placeholder(_)
+                | Token::LParen
+                | Token::Keyword(Keyword::Case | Keyword::Not | Keyword::Exists)
+        ),
+        _ => false,
+    }
+}
+
+// Parses the arguments of a function call after its opening parenthesis, returning
+// the call and the number of tokens consumed, including the closing parenthesis.
+fn parse_function_call(tokens: &[Token], name: &str, options: &ParserOptions, depth: usize) -> Result<(Expression, usize), ParseError> {
+    let mut pos = 0;
+    let distinct = matches!(tokens.get(pos), Some(Token::Keyword(Keyword::Distinct)));
+    if distinct {
+        pos += 1;
+    } else if is_all_quantifier(tokens) {
+        // `COUNT(ALL x)` spells out the default of keeping duplicates.
+        pos += 1;
+    }
+
+    let mut args = Vec::new();
+    if tokens.get(pos) == Some(&Token::Star) {
+        if distinct {
+            return Err(ParseError::InvalidExpression {
+                reason: format!("DISTINCT cannot be applied to '*' in {}()", name),
+                pos,
+            });
+        }
+        args.push(Expression::Wildcard);
+        pos += 1;
+    } else if tokens.get(pos) != Some(&Token::RParen) || distinct {
+        let (first, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+        return finish_function_call(tokens, name, first, pos + consumed, distinct, options, depth);
+    }
+
+    if tokens.get(pos) != Some(&Token::RParen) {
+        return Err(expected_at(tokens, pos, &format!("')' to close arguments of {}()", name)));
+    }
+    Ok((
+        Expression::Function {
+            name: name.to_string(),
+            args,
+            distinct,
+        },
+        pos + 1,
+    ))
+}
+
+// Parses the rest of a function call's arguments after the first one, `first`,
+// which ends at `pos`. Returns the call and the number of tokens consumed from
+// the start of `tokens`, including the closing parenthesis.
+fn finish_function_call(
+    tokens: &[Token],
+    name: &str,
+    first: Expression,
+    mut pos: usize,
+    distinct: bool,
+    options: &ParserOptions,
+    depth: usize,
+) -> Result<(Expression, usize), ParseError> {
+    let mut args = vec![first];
+    while tokens.get(pos) == Some(&Token::Comma) {
+        pos += 1;
+        let (arg, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+        pos += consumed;
+        args.push(arg);
+    }
+
+    if tokens.get(pos) != Some(&Token::RParen) {
+        return Err(expected_at(tokens, pos, &format!("')' to close arguments of {}()", name)));
+    }
+    Ok((
+        Expression::Function {
+            name: name.to_string(),
+            args,
+            distinct,
+        },
+        pos + 1,
+    ))
+}
+
+// Whether a call's arguments start with something only an ordinary function
+// call accepts: `*`, DISTINCT or ALL, or no arguments at all.
+fn starts_plain_arguments(tokens: &[Token]) -> bool {
+    matches!(tokens.first(), Some(Token::RParen | Token::Star | Token::Keyword(Keyword::Distinct))) || is_all_quantifier(tokens)
+}
+
+// Parses the arguments of TRIM after its opening parenthesis, returning the
+// expression and the number of tokens consumed, including the closing parenthesis.
+fn parse_trim(tokens: &[Token], options: &ParserOptions, depth: usize) -> Result<(Expression, usize), ParseError> {
+    let mut pos = 0;
+    let side = match tokens.get(pos) {
+        Some(Token::Keyword(Keyword::Leading)) => Some(TrimSide::Leading),
+        Some(Token::Keyword(Keyword::Trailing)) => Some(TrimSide::Trailing),
+        Some(Token::Keyword(Keyword::Both)) => Some(TrimSide::Both),
+        _ => None,
+    };
+    if side.is_some() {
+        pos += 1;
+    }
+
+    // `TRIM(LEADING FROM s)` names a side but no characters.
+    let first = if side.is_some() && tokens.get(pos) == Some(&Token::Keyword(Keyword::From)) {
+        None
+    } else {
+        let (first, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+        pos += consumed;
+        Some(first)
+    };
+    let (chars, expr) = match first {
+        // `TRIM([side] s)`: the only argument is the string.
+        Some(first) if tokens.get(pos) != Some(&Token::Keyword(Keyword::From)) => (None, first),
+        // Otherwise FROM is next and the string follows it.
+        chars => {
+            pos += 1;
+            let (expr, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+            pos += consumed;
+            (chars.map(Box::new), expr)
+        }
+    };
+
+    if tokens.get(pos) != Some(&Token::RParen) {
+        return Err(expected_at(tokens, pos, "')' to close TRIM"));
+    }
+    Ok((
+        Expression::Trim {
+            side: side.unwrap_or(TrimSide::Both),
+            chars,
+            expr: Box::new(expr),
+        },
+        pos + 1,
+    ))
+}
+
+// Parses the arguments of SUBSTRING after its opening parenthesis, returning the
+// expression and the number of tokens consumed, including the closing parenthesis.
+// Without FROM or FOR after the first argument it is an ordinary function call.
+fn parse_substring(tokens: &[Token], name: &str, options: &ParserOptions, depth: usize) -> Result<(Expression, usize), ParseError> {
+    if starts_plain_arguments(tokens) {
+        return parse_function_call(tokens, name, options, depth);
+    }
+    let (expr, mut pos) = parse_expression_at_depth(tokens, 0, options, depth + 1)?;
+    if !matches!(tokens.get(pos), Some(Token::Keyword(Keyword::From | Keyword::For))) {
+        return finish_function_call(tokens, name, expr, pos, false, options, depth);
+    }
+
+    let mut from = None;
+    if tokens.get(pos) == Some(&Token::Keyword(Keyword::From)) {
+        pos += 1;
+        let (start, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+        pos += consumed;
+        from = Some(Box::new(start));
+    }
+    let mut for_ = None;
+    if tokens.get(pos) == Some(&Token::Keyword(Keyword::For)) {
+        pos += 1;
+        let (length, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+        pos += consumed;
+        for_ = Some(Box::new(length));
+    }
+
+    if tokens.get(pos) != Some(&Token::RParen) {
+        return Err(expected_at(tokens, pos, "')' to close SUBSTRING"));
+    }
+    Ok((
+        Expression::Substring {
+            expr: Box::new(expr),
+            from,
+            for_,
+        },
+        pos + 1,
+    ))
+}
+
+// Parses the arguments of OVERLAY after its opening parenthesis, returning the
+// expression and the number of tokens consumed, including the closing parenthesis.
+// Without PLACING after the first argument it is an ordinary function call.
+fn parse_overlay(tokens: &[Token], name: &str, options: &ParserOptions, depth: usize) -> Result<(Expression, usize), ParseError> {
+    if starts_plain_arguments(tokens) {
+        return parse_function_call(tokens, name, options, depth);
+    }
+    let (string, mut pos) = parse_expression_at_depth(tokens, 0, options, depth + 1)?;
+    if tokens.get(pos) != Some(&Token::Keyword(Keyword::Placing)) {
+        return finish_function_call(tokens, name, string, pos, false, options, depth);
+    }
+    pos += 1;
+    let (replacement, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+    pos += consumed;
+
+    if tokens.get(pos) != Some(&Token::Keyword(Keyword::From)) {
+        return Err(expected_at(tokens, pos, "FROM in OVERLAY"));
+    }
+    pos += 1;
+    let (from, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+    pos += consumed;
+    let mut for_ = None;
+    if tokens.get(pos) == Some(&Token::Keyword(Keyword::For)) {
+        pos += 1;
+        let (length, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+        pos += consumed;
+        for_ = Some(Box::new(length));
+    }
+
+    if tokens.get(pos) != Some(&Token::RParen) {
+        return Err(expected_at(tokens, pos, "')' to close OVERLAY"));
+    }
+    Ok((
+        Expression::Overlay {
+            string: Box::new(string),
+            replacement: Box::new(replacement),
+            from: Box::new(from),
+            for_,
+        },
+        pos + 1,
+    ))
+}
+
+// Parses the arguments of POSITION after its opening parenthesis, returning the
+// expression and the number of tokens consumed, including the closing parenthesis.
+// The needle stops short of comparisons so that IN separates it from the
+// haystack instead of starting an IN predicate. Without IN after the first
+// argument it is an ordinary function call, and the needle is extended with the
+// comparisons and logical operators that follow it into that argument.
+fn parse_position(tokens: &[Token], name: &str, options: &ParserOptions, depth: usize) -> Result<(Expression, usize), ParseError> {
+    if starts_plain_arguments(tokens) {
+        return parse_function_call(tokens, name, options, depth);
+    }
+    let (needle, mut pos) = parse_expression_at_depth(tokens, COMPARISON_PRECEDENCE + 1, options, depth + 1)?;
+    if tokens.get(pos) != Some(&Token::Keyword(Keyword::In)) {
+        let (first, end) = parse_operators(tokens, needle, pos, 0, options, depth + 1)?;
+        return finish_function_call(tokens, name, first, end, false, options, depth);
+    }
+    pos += 1;
+    let (haystack, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+    pos += consumed;
+
+    if tokens.get(pos) != Some(&Token::RParen) {
+        return Err(expected_at(tokens, pos, "')' to close POSITION"));
+    }
+    Ok((
+        Expression::Position {
+            needle: Box::new(needle),
+            haystack: Box::new(haystack),
+        },
+        pos + 1,
+    ))
+}
+
+// Parses the body of a CASE expression after the CASE keyword, returning the
+// expression and the number of tokens consumed, including END.
+fn parse_case(tokens: &[Token], options: &ParserOptions, depth: usize) -> Result<(Expression, usize), ParseError> {
+    let mut pos = 0;
+    let is_keyword = |token: Option<&Token>, keyword: Keyword| token == Some(&Token::Keyword(keyword));
+
+    let operand = if is_keyword(tokens.get(pos), Keyword::When) {
+        None
+    } else {
+        let (operand, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+        pos += consumed;
+        Some(Box::new(operand))
+    };
+
+    let mut branches = Vec::new();
+    while is_keyword(tokens.get(pos), Keyword::When) {
+        pos += 1;
+        let (condition, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+        pos += consumed;
+        if !is_keyword(tokens.get(pos), Keyword::Then) {
+            return Err(expected_at(tokens, pos, "THEN after WHEN condition in CASE"));
+        }
+        pos += 1;
+        let (result, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+        pos += consumed;
+        branches.push((condition, result));
+    }
+    if branches.is_empty() {
+        return Err(ParseError::InvalidExpression {
+            reason: "CASE requires at least one WHEN clause".to_string(),
+            pos,
+        });
+    }
+
+    let else_result = if is_keyword(tokens.get(pos), Keyword::Else) {
+        pos += 1;
+        let (result, consumed) = parse_expression_at_depth(&tokens[pos..], 0, options, depth + 1).map_err(|e| e.offset(pos))?;
+        pos += consumed;
+        Some(Box::new(result))
+    } else {
+        None
+    };
+
+    if !is_keyword(tokens.get(pos), Keyword::End) {
+        return Err(expected_at(tokens, pos, "END to close CASE expression"));
+    }
+    Ok((
+        Expression::Case {
+            operand,
+            branches,
+            else_result,
+        },
+        pos + 1,
+    ))
+}
+
+const COMPARISON_PRECEDENCE: u8 = 3;
+const AT_TIME_ZONE_PRECEDENCE: u8 = 7;
+
+fn get_precedence(op: &str) -> u8 {
+    match op {
+        "OR" => 1,
+        "AND" => 2,
+        "=" | "!=" | "<" | ">" | "<=" | ">=" | "~" | "~*" | "!~" | "!~*" => COMPARISON_PRECEDENCE,
+        "+" | "-" => 4,
+        "*" | "/" => 5,
+        "^" => 6,
+        _ => 0,
+    }
+}
+
+/// How a chain of operators of equal precedence groups.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Assoc {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a ^ b ^ c` is `a ^ (b ^ c)`.
+    Right,
+}
+
+pub fn associativity(op: &BinaryOperator) -> Assoc {
+    match op {
+        BinaryOperator::Power => Assoc::Right,
+        _ => Assoc::Left,
+    }
+}
+
+fn to_binary_operator(op: &str) -> Option<BinaryOperator> {
+    match op {
+        "OR" => Some(BinaryOperator::Or),
+        "AND" => Some(BinaryOperator::And),
+        "=" => Some(BinaryOperator::Equal),
+        "!=" => Some(BinaryOperator::NotEqual),
+        "<" => Some(BinaryOperator::Less),
+        "<=" => Some(BinaryOperator::LessEqual),
+        ">" => Some(BinaryOperator::Greater),
+        ">=" => Some(BinaryOperator::GreaterEqual),
+        "+" => Some(BinaryOperator::Add),
+        "-" => Some(BinaryOperator::Subtract),
+        "*" => Some(BinaryOperator::Multiply),
+        "/" => Some(BinaryOperator::Divide),
+        "^" => Some(BinaryOperator::Power),
+        "~" => Some(BinaryOperator::RegexMatch),
+        "~*" => Some(BinaryOperator::RegexMatchCI),
+        "!~" => Some(BinaryOperator::RegexNotMatch),
+        "!~*" => Some(BinaryOperator::RegexNotMatchCI),
+        other => Some(BinaryOperator::Unknown(other.to_string())),
+    }
+}
+// The precedence of `op` as `get_precedence` gives it for its token, with the
+// keyword operators at the comparison level they are parsed at.
+fn operator_precedence(op: &BinaryOperator) -> u8 {
+    match op {
+        BinaryOperator::Or => 1,
+        BinaryOperator::And => 2,
+        BinaryOperator::Add | BinaryOperator::Subtract => 4,
+        BinaryOperator::Multiply | BinaryOperator::Divide => 5,
+        BinaryOperator::Power => 6,
+        BinaryOperator::Unknown(op) => get_precedence(op),
+        _ => COMPARISON_PRECEDENCE,
+    }
+}
+
+// How tightly `expr` holds together as an operand, on the scale of
+// `get_precedence`. NOT takes everything up to AT TIME ZONE after it, so it
+// sits at that level, and AT TIME ZONE itself just above; anything that is
+// not an operator application binds tightest.
+fn binding_power(expr: &Expression) -> u8 {
+    match expr {
+        Expression::BinaryOp { op, .. } => operator_precedence(op),
+        Expression::QuantifiedComparison { .. }
+        | Expression::InList { .. }
+        | Expression::InSubquery { .. }
+        | Expression::Between { .. }
+        | Expression::Like { .. }
+        | Expression::IsPredicate { .. } => COMPARISON_PRECEDENCE,
+        Expression::UnaryOp { .. } => AT_TIME_ZONE_PRECEDENCE,
+        Expression::AtTimeZone { .. } => AT_TIME_ZONE_PRECEDENCE + 1,
+        Expression::Aliased { .. } => 0,
+        _ => AT_TIME_ZONE_PRECEDENCE + 2,
+    }
+}
+
+/// Formats `items` separated by commas.
+pub(crate) struct CommaSeparated<'a, T>(pub &'a [T]);
+
+impl<T: fmt::Display> fmt::Display for CommaSeparated<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, item) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", item)?;
+        }
+        Ok(())
+    }
+}
+
+// An operand that must bind at least `min` tightly to be read back as one,
+// parenthesized when it does not.
+struct Operand<'a>(&'a Expression, u8);
+
+impl fmt::Display for Operand<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if binding_power(self.0) < self.1 {
+            write!(f, "({})", self.0)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+/// Formats the expression as SQL that parses back to an equal expression,
+/// adding parentheses only where precedence requires them.
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Operands of a comparison-level predicate, and the arithmetic that
+        // BETWEEN bounds and LIKE patterns stop at.
+        const PREDICATE: u8 = COMPARISON_PRECEDENCE;
+        const ARITHMETIC: u8 = COMPARISON_PRECEDENCE + 1;
+        let not = |negated: bool| if negated { "NOT " } else { "" };
+        match self {
+            Expression::Number(n) => write!(f, "{}", n),
+            // `SELECT *` keeps its star as a column name.
+            Expression::Identifier(name) if &**name == "*" => f.write_str("*"),
+            Expression::Identifier(name) => write!(f, "{}", Name(name)),
+            Expression::QualifiedName(parts) => f.write_str(&parts.join(".")),
+            Expression::String(s) => write!(f, "'{}'", s),
+            Expression::Bool(true) => f.write_str("TRUE"),
+            Expression::Bool(false) => f.write_str("FALSE"),
+            Expression::Unknown => f.write_str("UNKNOWN"),
+            Expression::Placeholder(kind) => write!(f, "{}", kind),
+            Expression::Subquery(query) => write!(f, "({})", query),
+            Expression::Function { name, args, distinct } => {
+                let distinct = if *distinct { "DISTINCT " } else { "" };
+                write!(f, "{}({}{})", Name(name), distinct, CommaSeparated(args))
+            }
+            Expression::Wildcard => f.write_str("*"),
+            Expression::QualifiedStar(table) => write!(f, "{}.*", Name(table)),
+            Expression::Aliased { expr, alias } => write!(f, "{} AS {}", expr, Name(alias)),
+            Expression::QuantifiedComparison {
+                left,
+                op,
+                quantifier,
+                subquery,
+            } => write!(f, "{} {} {} ({})", Operand(left, PREDICATE), op, quantifier, subquery),
+            Expression::Exists { subquery, negated } => write!(f, "{}EXISTS ({})", not(*negated), subquery),
+            Expression::InList { expr, list, negated } => {
+                write!(f, "{} {}IN ({})", Operand(expr, PREDICATE), not(*negated), CommaSeparated(list))
+            }
+            Expression::InSubquery { expr, subquery, negated } => {
+                write!(f, "{} {}IN ({})", Operand(expr, PREDICATE), not(*negated), subquery)
+            }
+            Expression::Between { expr, low, high, negated } => write!(
+                f,
+                "{} {}BETWEEN {} AND {}",
+                Operand(expr, PREDICATE),
+                not(*negated),
+                Operand(low, ARITHMETIC),
+                Operand(high, ARITHMETIC)
+            ),
+            Expression::Like {
+                expr,
+                pattern,
+                negated,
+                escape,
+                case_insensitive,
+            } => {
+                let like = if *case_insensitive { "ILIKE" } else { "LIKE" };
+                write!(f, "{} {}{} {}", Operand(expr, PREDICATE), not(*negated), like, Operand(pattern, ARITHMETIC))?;
+                if let Some(escape) = escape {
+                    write!(f, " ESCAPE {}", Operand(escape, ARITHMETIC))?;
+                }
+                Ok(())
+            }
+            Expression::Case {
+                operand,
+                branches,
+                else_result,
+            } => {
+                f.write_str("CASE")?;
+                if let Some(operand) = operand {
+                    write!(f, " {}", operand)?;
+                }
+                for (condition, result) in branches {
+                    write!(f, " WHEN {} THEN {}", condition, result)?;
+                }
+                if let Some(else_result) = else_result {
+                    write!(f, " ELSE {}", else_result)?;
+                }
+                f.write_str(" END")
+            }
+            Expression::IsPredicate { expr, negated, target } => {
+                write!(f, "{} IS {}{}", Operand(expr, PREDICATE), not(*negated), target)
+            }
+            // BOTH is the default side and is left out.
+            Expression::Trim {
+                side: TrimSide::Both,
+                chars: None,
+                expr,
+            } => write!(f, "TRIM({})", expr),
+            Expression::Trim { side, chars, expr } => {
+                f.write_str("TRIM(")?;
+                if *side != TrimSide::Both {
+                    write!(f, "{} ", side)?;
+                }
+                if let Some(chars) = chars {
+                    write!(f, "{} ", chars)?;
+                }
+                write!(f, "FROM {})", expr)
+            }
+            Expression::Substring { expr, from, for_ } => {
+                write!(f, "SUBSTRING({}", expr)?;
+                if let Some(from) = from {
+                    write!(f, " FROM {}", from)?;
+                }
+                if let Some(for_) = for_ {
+                    write!(f, " FOR {}", for_)?;
+                }
+                f.write_str(")")
+            }
+            Expression::Overlay {
+                string,
+                replacement,
+                from,
+                for_,
+            } => {
+                write!(f, "OVERLAY({} PLACING {} FROM {}", string, replacement, from)?;
+                if let Some(for_) = for_ {
+                    write!(f, " FOR {}", for_)?;
+                }
+                f.write_str(")")
+            }
+            Expression::Position { needle, haystack } => {
+                write!(f, "POSITION({} IN {})", Operand(needle, ARITHMETIC), haystack)
+            }
+            Expression::AtTimeZone { expr, zone } => write!(
+                f,
+                "{} AT TIME ZONE {}",
+                Operand(expr, AT_TIME_ZONE_PRECEDENCE + 1),
+                Operand(zone, AT_TIME_ZONE_PRECEDENCE + 2)
+            ),
+            // `NOT (EXISTS ...)` stays a NOT around the predicate; without the
+            // parentheses it would read back as a negated EXISTS.
+            Expression::UnaryOp {
+                op: UnaryOperator::Not,
+                expr,
+            } if matches!(**expr, Expression::Exists { .. }) => write!(f, "NOT ({})", expr),
+            Expression::UnaryOp {
+                op: UnaryOperator::Not,
+                expr,
+            } => write!(f, "NOT {}", Operand(expr, AT_TIME_ZONE_PRECEDENCE)),
+            Expression::BinaryOp { left, op, right } => {
+                let prec = operator_precedence(op);
+                let (left_min, right_min) = match associativity(op) {
+                    Assoc::Left => (prec, prec + 1),
+                    Assoc::Right => (prec + 1, prec),
+                };
+                write!(f, "{} {} {}", Operand(left, left_min), op, Operand(right, right_min))
+            }
+        }
+    }
+}
+
+impl fmt::Display for IsTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IsTarget::Null => "NULL",
+            IsTarget::True => "TRUE",
+            IsTarget::False => "FALSE",
+            IsTarget::Unknown => "UNKNOWN",
+        })
+    }
+}
+
+impl fmt::Display for TrimSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TrimSide::Leading => "LEADING",
+            TrimSide::Trailing => "TRAILING",
+            TrimSide::Both => "BOTH",
+        })
+    }
+}
+
+impl fmt::Display for AnyOrAll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AnyOrAll::Any => "ANY",
+            AnyOrAll::All => "ALL",
+        })
+    }
+}
+
+impl fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BinaryOperator::Or => "OR",
+            BinaryOperator::And => "AND",
+            BinaryOperator::Equal => "=",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::Less => "<",
+            BinaryOperator::LessEqual => "<=",
+            BinaryOperator::Greater => ">",
+            BinaryOperator::GreaterEqual => ">=",
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Power => "^",
+            BinaryOperator::IsDistinctFrom => "IS DISTINCT FROM",
+            BinaryOperator::IsNotDistinctFrom => "IS NOT DISTINCT FROM",
+            BinaryOperator::SimilarTo => "SIMILAR TO",
+            BinaryOperator::NotSimilarTo => "NOT SIMILAR TO",
+            BinaryOperator::RegexMatch => "~",
+            BinaryOperator::RegexMatchCI => "~*",
+            BinaryOperator::RegexNotMatch => "!~",
+            BinaryOperator::RegexNotMatchCI => "!~*",
+            BinaryOperator::Unknown(op) => op,
+        })
+    }
+}
+
+/// Calls `f` with every column name in `expr`. Subqueries have a scope of their
+/// own and are not descended into.
+pub fn for_each_identifier<F: FnMut(&str)>(expr: &Expression, f: &mut F) {
+    match expr {
+        Expression::Identifier(name) => f(name),
+        Expression::Function { args, .. } => {
+            for arg in args {
+                for_each_identifier(arg, f);
+            }
+        }
+        Expression::QuantifiedComparison { left, .. } => for_each_identifier(left, f),
+        Expression::InList { expr, list, .. } => {
+            for_each_identifier(expr, f);
+            for item in list {
+                for_each_identifier(item, f);
+            }
+        }
+        Expression::InSubquery { expr, .. } => for_each_identifier(expr, f),
+        Expression::Between { expr, low, high, .. } => {
+            for_each_identifier(expr, f);
+            for_each_identifier(low, f);
+            for_each_identifier(high, f);
+        }
+        Expression::Like {
+            expr,
+            pattern,
+            escape,
+            ..
+        } => {
+            for_each_identifier(expr, f);
+            for_each_identifier(pattern, f);
+            if let Some(escape) = escape {
+                for_each_identifier(escape, f);
+            }
+        }
+        Expression::Case {
+            operand,
+            branches,
+            else_result,
+        } => {
+            if let Some(operand) = operand {
+                for_each_identifier(operand, f);
+            }
+            for (condition, result) in branches {
+                for_each_identifier(condition, f);
+                for_each_identifier(result, f);
+            }
+            if let Some(else_result) = else_result {
+                for_each_identifier(else_result, f);
+            }
+        }
+        Expression::IsPredicate { expr, .. } | Expression::UnaryOp { expr, .. } | Expression::Aliased { expr, .. } => {
+            for_each_identifier(expr, f)
+        }
+        Expression::AtTimeZone { expr, zone } => {
+            for_each_identifier(expr, f);
+            for_each_identifier(zone, f);
+        }
+        Expression::Position { needle, haystack } => {
+            for_each_identifier(needle, f);
+            for_each_identifier(haystack, f);
+        }
+        Expression::Trim { chars, expr, .. } => {
+            if let Some(chars) = chars {
+                for_each_identifier(chars, f);
+            }
+            for_each_identifier(expr, f);
+        }
+        Expression::Substring { expr, from, for_ } => {
+            for_each_identifier(expr, f);
+            for arg in from.iter().chain(for_) {
+                for_each_identifier(arg, f);
+            }
+        }
+        Expression::Overlay {
+            string,
+            replacement,
+            from,
+            for_,
+        } => {
+            for arg in [string, replacement, from].into_iter().chain(for_) {
+                for_each_identifier(arg, f);
+            }
+        }
+        Expression::BinaryOp { left, right, .. } => {
+            for_each_identifier(left, f);
+            for_each_identifier(right, f);
+        }
+        Expression::Number(_)
+        | Expression::QualifiedName(_)
+        | Expression::String(_)
+        | Expression::Bool(_)
+        | Expression::Unknown
+        | Expression::Placeholder(_)
+        | Expression::Subquery(_)
+        | Expression::Exists { .. }
+        | Expression::Wildcard
+        | Expression::QualifiedStar(_) => {}
+    }
+}
+
+/// Rewrites an expression into a canonical form so that semantically equal
+/// expressions compare equal: double negations are removed, chains of the
+/// commutative and associative operators (AND, OR, +, *) are flattened, sorted
+/// and rebuilt left-associatively, and the two sides of `=` are put in order.
+/// Operands are ordered by their `Debug` rendering, which is deterministic.
+/// Every operand, argument and branch is normalized too; subqueries are not.
+pub fn normalize(expr: Expression) -> Expression {
+    match expr {
+        Expression::UnaryOp {
+            op: UnaryOperator::Not,
+            expr,
+        } => match normalize(*expr) {
+            Expression::UnaryOp {
+                op: UnaryOperator::Not,
+                expr: inner,
+            } => *inner,
+            other => Expression::UnaryOp {
+                op: UnaryOperator::Not,
+                expr: Box::new(other),
+            },
+        },
+        Expression::BinaryOp { left, op, right } => match op {
+            BinaryOperator::And | BinaryOperator::Or | BinaryOperator::Add | BinaryOperator::Multiply => {
+                let mut operands = Vec::new();
+                flatten_operands(normalize(*left), &op, &mut operands);
+                flatten_operands(normalize(*right), &op, &mut operands);
+                operands.sort_by_cached_key(|operand| format!("{:?}", operand));
+                operands
+                    .into_iter()
+                    .reduce(|acc, operand| Expression::BinaryOp {
+                        left: Box::new(acc),
+                        op: op.clone(),
+                        right: Box::new(operand),
+                    })
+                    .expect("a binary operator has two operands")
+            }
+            BinaryOperator::Equal => {
+                let mut sides = [normalize(*left), normalize(*right)];
+                sides.sort_by_cached_key(|side| format!("{:?}", side));
+                let [left, right] = sides;
+                Expression::BinaryOp {
+                    left: Box::new(left),
+                    op,
+                    right: Box::new(right),
+                }
+            }
+            _ => Expression::BinaryOp {
+                left: Box::new(normalize(*left)),
+                op,
+                right: Box::new(normalize(*right)),
+            },
+        },
+        Expression::Function { name, args, distinct } => Expression::Function {
+            name,
+            args: args.into_iter().map(normalize).collect(),
+            distinct,
+        },
+        Expression::QuantifiedComparison {
+            left,
+            op,
+            quantifier,
+            subquery,
+        } => Expression::QuantifiedComparison {
+            left: normalize_boxed(left),
+            op,
+            quantifier,
+            subquery,
+        },
+        Expression::InList { expr, list, negated } => Expression::InList {
+            expr: normalize_boxed(expr),
+            list: list.into_iter().map(normalize).collect(),
+            negated,
+        },
+        Expression::InSubquery { expr, subquery, negated } => Expression::InSubquery {
+            expr: normalize_boxed(expr),
+            subquery,
+            negated,
+        },
+        Expression::Between {
+            expr,
+            low,
+            high,
+            negated,
+        } => Expression::Between {
+            expr: normalize_boxed(expr),
+            low: normalize_boxed(low),
+            high: normalize_boxed(high),
+            negated,
+        },
+        Expression::Like {
+            expr,
+            pattern,
+            negated,
+            escape,
+            case_insensitive,
+        } => Expression::Like {
+            expr: normalize_boxed(expr),
+            pattern: normalize_boxed(pattern),
+            negated,
+            escape: escape.map(normalize_boxed),
+            case_insensitive,
+        },
+        Expression::Case {
+            operand,
+            branches,
+            else_result,
+        } => Expression::Case {
+            operand: operand.map(normalize_boxed),
+            branches: branches
+                .into_iter()
+                .map(|(condition, result)| (normalize(condition), normalize(result)))
+                .collect(),
+            else_result: else_result.map(normalize_boxed),
+        },
+        Expression::IsPredicate { expr, negated, target } => Expression::IsPredicate {
+            expr: normalize_boxed(expr),
+            negated,
+            target,
+        },
+        Expression::Trim { side, chars, expr } => Expression::Trim {
+            side,
+            chars: chars.map(normalize_boxed),
+            expr: normalize_boxed(expr),
+        },
+        Expression::Substring { expr, from, for_ } => Expression::Substring {
+            expr: normalize_boxed(expr),
+            from: from.map(normalize_boxed),
+            for_: for_.map(normalize_boxed),
+        },
+        Expression::Overlay {
+            string,
+            replacement,
+            from,
+            for_,
+        } => Expression::Overlay {
+            string: normalize_boxed(string),
+            replacement: normalize_boxed(replacement),
+            from: normalize_boxed(from),
+            for_: for_.map(normalize_boxed),
+        },
+        Expression::Position { needle, haystack } => Expression::Position {
+            needle: normalize_boxed(needle),
+            haystack: normalize_boxed(haystack),
+        },
+        Expression::AtTimeZone { expr, zone } => Expression::AtTimeZone {
+            expr: normalize_boxed(expr),
+            zone: normalize_boxed(zone),
+        },
+        Expression::Aliased { expr, alias } => Expression::Aliased {
+            expr: normalize_boxed(expr),
+            alias,
+        },
+        // Subqueries are statements and are left as written.
+        other @ (Expression::Number(_)
+        | Expression::Identifier(_)
+        | Expression::QualifiedName(_)
+        | Expression::String(_)
+        | Expression::Bool(_)
+        | Expression::Unknown
+        | Expression::Placeholder(_)
+        | Expression::Subquery(_)
+        | Expression::Exists { .. }
+        | Expression::Wildcard
+        | Expression::QualifiedStar(_)) => other,
+    }
+}
+
+// Normalizes a boxed operand in place, reusing its allocation.
+fn normalize_boxed(mut expr: Box<Expression>) -> Box<Expression> {
+    *expr = normalize(*expr);
+    expr
+}
+
+// Collects the operands of an already-normalized chain of `op` applications.
+fn flatten_operands(expr: Expression, op: &BinaryOperator, operands: &mut Vec<Expression>) {
+    match expr {
+        Expression::BinaryOp {
+            left,
+            op: inner,
+            right,
+        } if inner == *op => {
+            flatten_operands(*left, op, operands);
+            flatten_operands(*right, op, operands);
+        }
+        other => operands.push(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::tokenize;
+
+    fn expr(sql: &str) -> Expression {
+        parse_expression(&tokenize(sql), 0).unwrap().0
+    }
+
+    fn normalized(sql: &str) -> Expression {
+        normalize(expr(sql))
+    }
+
+    #[test]
+    fn normalize_orders_commutative_operands() {
+        assert_eq!(normalized("a AND b"), normalized("b AND a"));
+        assert_eq!(normalized("a = 1"), normalized("1 = a"));
+        assert_eq!(normalized("(a + b) + c"), normalized("c + (b + a)"));
+        assert_eq!(normalized("NOT NOT a"), expr("a"));
+    }
+
+    #[test]
+    fn normalize_keeps_non_commutative_operands() {
+        assert_ne!(normalized("a - b"), normalized("b - a"));
+        assert_ne!(normalized("a / b"), normalized("b / a"));
+        assert_ne!(normalized("a < b"), normalized("b < a"));
+    }
+
+    #[test]
+    fn normalize_recurses_into_children() {
+        assert_eq!(normalized("f(a AND b)"), normalized("f(b AND a)"));
+        assert_eq!(normalized("x IN (a + b, c)"), normalized("x IN (b + a, c)"));
+        assert_eq!(normalized("x BETWEEN a * b AND c"), normalized("x BETWEEN b * a AND c"));
+        assert_eq!(
+            normalized("CASE WHEN a OR b THEN 1 ELSE c + d END"),
+            normalized("CASE WHEN b OR a THEN 1 ELSE d + c END")
+        );
+        assert_eq!(normalized("(a AND b) IS NULL"), normalized("(b AND a) IS NULL"));
+        assert_ne!(normalized("f(a - b)"), normalized("f(b - a)"));
+    }
+
+    #[test]
+    fn between_takes_only_the_first_and() {
+        assert_eq!(
+            expr("a BETWEEN 1 AND 10 AND b = 2"),
+            Expression::BinaryOp {
+                left: Box::new(Expression::Between {
+                    expr: Box::new(expr("a")),
+                    low: Box::new(Expression::Number(1)),
+                    high: Box::new(Expression::Number(10)),
+                    negated: false,
+                }),
+                op: BinaryOperator::And,
+                right: Box::new(expr("b = 2")),
+            }
+        );
+        assert_eq!(
+            expr("a NOT BETWEEN b + 1 AND c * 2"),
+            Expression::Between {
+                expr: Box::new(expr("a")),
+                low: Box::new(expr("b + 1")),
+                high: Box::new(expr("c * 2")),
+                negated: true,
+            }
+        );
+    }
+
+    #[test]
+    fn all_is_contextual() {
+        assert_eq!(expr("count(ALL x)"), expr("count(x)"));
+        assert_eq!(
+            expr("count(all)"),
+            Expression::Function {
+                name: "count".to_string(),
+                args: vec![expr("all")],
+                distinct: false,
+            }
+        );
+        match expr("a > ALL (SELECT b FROM t)") {
+            Expression::QuantifiedComparison { quantifier, .. } => assert_eq!(quantifier, AnyOrAll::All),
+            other => panic!("expected a quantified comparison, got {:?}", other),
+        }
+        assert_eq!(
+            expr("a > all"),
+            Expression::BinaryOp {
+                left: Box::new(expr("a")),
+                op: BinaryOperator::Greater,
+                right: Box::new(expr("all")),
+            }
+        );
+    }
+
+    #[test]
+    fn at_time_zone_words_are_column_names_elsewhere() {
+        assert_eq!(
+            expr("ts AT TIME ZONE 'UTC'"),
+            Expression::AtTimeZone {
+                expr: Box::new(expr("ts")),
+                zone: Box::new(Expression::String("UTC".to_string())),
+            }
+        );
+        assert_eq!(
+            expr("at + time * zone"),
+            Expression::BinaryOp {
+                left: Box::new(expr("at")),
+                op: BinaryOperator::Add,
+                right: Box::new(expr("time * zone")),
+            }
+        );
+    }
+
+    #[test]
+    fn power_is_right_associative() {
+        let power = |left, right| Expression::BinaryOp {
+            left: Box::new(left),
+            op: BinaryOperator::Power,
+            right: Box::new(right),
+        };
+        assert_eq!(
+            expr("2 ^ 3 ^ 2"),
+            power(Expression::Number(2), power(Expression::Number(3), Expression::Number(2)))
+        );
+        assert_eq!(
+            expr("2 * 3 ^ 2"),
+            Expression::BinaryOp {
+                left: Box::new(Expression::Number(2)),
+                op: BinaryOperator::Multiply,
+                right: Box::new(power(Expression::Number(3), Expression::Number(2))),
+            }
+        );
+    }
+
+    #[test]
+    fn long_chains_are_left_associative() {
+        let terms = 1000;
+        let sql = (1..=terms).map(|n| n.to_string()).collect::<Vec<_>>().join(" - ");
+        let (parsed, consumed) = parse_expression(&tokenize(&sql), 0).unwrap();
+        assert_eq!(consumed, 2 * terms as usize - 1);
+        // `((1 - 2) - 3) - ...`: every right operand is a single term, and the
+        // left spine holds the rest of the chain.
+        let mut node = &parsed;
+        for n in (2..=terms).rev() {
+            match node {
+                Expression::BinaryOp {
+                    left,
+                    op: BinaryOperator::Subtract,
+                    right,
+                } => {
+                    assert_eq!(**right, Expression::Number(n));
+                    node = left;
+                }
+                other => panic!("expected a subtraction, got {:?}", other),
+            }
+        }
+        assert_eq!(*node, Expression::Number(1));
+    }
+
+    #[test]
+    fn substring_arguments_are_parsed_once() {
+        // Each level parses its first argument once, so deep nesting stays linear.
+        let levels = 40;
+        let sql = format!("{}s{}", "substring(".repeat(levels), ", 1)".repeat(levels));
+        assert!(parse_expression(&tokenize(&sql), 0).is_ok());
+        assert_eq!(
+            expr("substring(s FROM 2 FOR 3)"),
+            Expression::Substring {
+                expr: Box::new(expr("s")),
+                from: Some(Box::new(Expression::Number(2))),
+                for_: Some(Box::new(Expression::Number(3))),
+            }
+        );
+        assert_eq!(
+            expr("substring(s, 2)"),
+            Expression::Function {
+                name: "substring".to_string(),
+                args: vec![expr("s"), Expression::Number(2)],
+                distinct: false,
+            }
+        );
+        assert!(matches!(expr("substring(DISTINCT s)"), Expression::Function { distinct: true, .. }));
+    }
+
+    #[test]
+    fn overlay_arguments_are_parsed_once() {
+        let levels = 40;
+        let sql = format!("{}s{}", "overlay(".repeat(levels), ", 1)".repeat(levels));
+        assert!(parse_expression(&tokenize(&sql), 0).is_ok());
+        assert_eq!(
+            expr("overlay(s PLACING 'x' FROM 2)"),
+            Expression::Overlay {
+                string: Box::new(expr("s")),
+                replacement: Box::new(Expression::String("x".to_string())),
+                from: Box::new(Expression::Number(2)),
+                for_: None,
+            }
+        );
+        assert_eq!(
+            expr("overlay(s, 'x')"),
+            Expression::Function {
+                name: "overlay".to_string(),
+                args: vec![expr("s"), Expression::String("x".to_string())],
+                distinct: false,
+            }
+        );
+    }
+
+    #[test]
+    fn position_arguments_are_parsed_once() {
+        let levels = 40;
+        let sql = format!("{}s{}", "position(".repeat(levels), ", 1)".repeat(levels));
+        assert!(parse_expression(&tokenize(&sql), 0).is_ok());
+        assert_eq!(
+            expr("position('b' IN s + t)"),
+            Expression::Position {
+                needle: Box::new(Expression::String("b".to_string())),
+                haystack: Box::new(expr("s + t")),
+            }
+        );
+        // Without IN the needle grows back into a whole first argument.
+        assert_eq!(
+            expr("position(a + 1 = b AND c, d)"),
+            Expression::Function {
+                name: "position".to_string(),
+                args: vec![expr("a + 1 = b AND c"), expr("d")],
+                distinct: false,
+            }
+        );
+    }
+
+    #[test]
+    fn in_takes_a_list_or_a_subquery() {
+        let in_list = |negated| Expression::InList {
+            expr: Box::new(expr("a")),
+            list: vec![Expression::Number(1), Expression::Number(2)],
+            negated,
+        };
+        assert_eq!(expr("a IN (1, 2)"), in_list(false));
+        assert_eq!(expr("a NOT IN (1, 2)"), in_list(true));
+        assert!(matches!(expr("a IN (SELECT b FROM t)"), Expression::InSubquery { negated: false, .. }));
+        assert!(matches!(expr("a NOT IN (SELECT b FROM t)"), Expression::InSubquery { negated: true, .. }));
+        // IN binds like a comparison, tighter than AND.
+        assert_eq!(
+            expr("a IN (1, 2) AND b = 3"),
+            Expression::BinaryOp {
+                left: Box::new(in_list(false)),
+                op: BinaryOperator::And,
+                right: Box::new(expr("b = 3")),
+            }
+        );
+    }
+
+    #[test]
+    fn empty_in_list_is_an_error() {
+        assert_eq!(
+            parse_expression(&tokenize("a IN ()"), 0),
+            Err(ParseError::InvalidExpression {
+                reason: "IN list must not be empty".to_string(),
+                pos: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn like_with_escape_and_logical_operators() {
+        let like = |negated, escape: Option<Expression>| Expression::Like {
+            expr: Box::new(expr("name")),
+            pattern: Box::new(Expression::String("a%".to_string())),
+            negated,
+            escape: escape.map(Box::new),
+            case_insensitive: false,
+        };
+        assert_eq!(expr("name LIKE 'a%'"), like(false, None));
+        assert_eq!(
+            expr("name NOT LIKE 'a%' ESCAPE '\\'"),
+            like(true, Some(Expression::String("\\".to_string())))
+        );
+        // LIKE binds like a comparison, so AND and OR take whole predicates.
+        assert_eq!(
+            expr("name LIKE 'a%' AND b OR c"),
+            Expression::BinaryOp {
+                left: Box::new(Expression::BinaryOp {
+                    left: Box::new(like(false, None)),
+                    op: BinaryOperator::And,
+                    right: Box::new(expr("b")),
+                }),
+                op: BinaryOperator::Or,
+                right: Box::new(expr("c")),
+            }
+        );
+        assert_eq!(
+            expr("x = 1 OR name NOT LIKE 'a%'"),
+            Expression::BinaryOp {
+                left: Box::new(expr("x = 1")),
+                op: BinaryOperator::Or,
+                right: Box::new(like(true, None)),
+            }
+        );
+    }
+
+    #[test]
+    fn like_without_pattern_is_an_error() {
+        assert_eq!(
+            parse_expression(&tokenize("name LIKE"), 0),
+            Err(ParseError::UnexpectedEof {
+                expected: vec!["pattern after LIKE".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn is_predicates_under_and_or() {
+        let is = |column: &str, negated, target| Expression::IsPredicate {
+            expr: Box::new(expr(column)),
+            negated,
+            target,
+        };
+        assert_eq!(
+            expr("a IS NULL OR b IS NOT NULL"),
+            Expression::BinaryOp {
+                left: Box::new(is("a", false, IsTarget::Null)),
+                op: BinaryOperator::Or,
+                right: Box::new(is("b", true, IsTarget::Null)),
+            }
+        );
+        assert_eq!(
+            expr("flag IS TRUE AND x > 0"),
+            Expression::BinaryOp {
+                left: Box::new(is("flag", false, IsTarget::True)),
+                op: BinaryOperator::And,
+                right: Box::new(expr("x > 0")),
+            }
+        );
+    }
+
+    #[test]
+    fn is_followed_by_a_bad_token_is_an_error() {
+        let error = |pos| {
+            Err(ParseError::UnexpectedToken {
+                found: Token::Number(5),
+                expected: vec!["NULL, TRUE, FALSE, UNKNOWN or DISTINCT FROM after IS".to_string()],
+                pos,
+            })
+        };
+        assert_eq!(parse_expression(&tokenize("a IS 5"), 0), error(2));
+        assert_eq!(parse_expression(&tokenize("a IS NOT 5"), 0), error(3));
+    }
+
+    #[test]
+    fn searched_and_simple_case() {
+        assert_eq!(
+            expr("CASE WHEN a > 0 THEN 'pos' WHEN a < 0 THEN 'neg' ELSE 'zero' END"),
+            Expression::Case {
+                operand: None,
+                branches: vec![
+                    (expr("a > 0"), Expression::String("pos".to_string())),
+                    (expr("a < 0"), Expression::String("neg".to_string())),
+                ],
+                else_result: Some(Box::new(Expression::String("zero".to_string()))),
+            }
+        );
+        assert_eq!(
+            expr("CASE status WHEN 1 THEN 'open' WHEN 2 THEN 'closed' END"),
+            Expression::Case {
+                operand: Some(Box::new(expr("status"))),
+                branches: vec![
+                    (Expression::Number(1), Expression::String("open".to_string())),
+                    (Expression::Number(2), Expression::String("closed".to_string())),
+                ],
+                else_result: None,
+            }
+        );
+    }
+
+    #[test]
+    fn nested_case() {
+        assert_eq!(
+            expr("CASE WHEN a THEN CASE b WHEN 1 THEN x END ELSE y END"),
+            Expression::Case {
+                operand: None,
+                branches: vec![(expr("a"), expr("CASE b WHEN 1 THEN x END"))],
+                else_result: Some(Box::new(expr("y"))),
+            }
+        );
+    }
+
+    #[test]
+    fn case_without_when_is_an_error() {
+        assert_eq!(
+            parse_expression(&tokenize("CASE status ELSE 1 END"), 0),
+            Err(ParseError::InvalidExpression {
+                reason: "CASE requires at least one WHEN clause".to_string(),
+                pos: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn exists_under_and_or() {
+        let subquery = match expr("EXISTS (SELECT 1 FROM t)") {
+            Expression::Exists { subquery, negated: false } => subquery,
+            other => panic!("expected EXISTS, got {:?}", other),
+        };
+        let exists = |negated| Expression::Exists {
+            subquery: subquery.clone(),
+            negated,
+        };
+        assert_eq!(expr("NOT EXISTS (SELECT 1 FROM t)"), exists(true));
+        assert_eq!(
+            expr("EXISTS (SELECT 1 FROM t) AND a = 1"),
+            Expression::BinaryOp {
+                left: Box::new(exists(false)),
+                op: BinaryOperator::And,
+                right: Box::new(expr("a = 1")),
+            }
+        );
+        assert_eq!(
+            expr("a = 1 OR NOT EXISTS (SELECT 1 FROM t)"),
+            Expression::BinaryOp {
+                left: Box::new(expr("a = 1")),
+                op: BinaryOperator::Or,
+                right: Box::new(exists(true)),
+            }
+        );
+        // NOT folds into the EXISTS only; the AND still takes the whole predicate.
+        assert_eq!(
+            expr("NOT EXISTS (SELECT 1 FROM t) AND EXISTS (SELECT 1 FROM t) OR b"),
+            Expression::BinaryOp {
+                left: Box::new(Expression::BinaryOp {
+                    left: Box::new(exists(true)),
+                    op: BinaryOperator::And,
+                    right: Box::new(exists(false)),
+                }),
+                op: BinaryOperator::Or,
+                right: Box::new(expr("b")),
+            }
+        );
+    }
+
+    #[test]
+    fn every_comparison_takes_any_some_and_all() {
+        let subquery = match expr("a = ANY (SELECT b FROM t)") {
+            Expression::QuantifiedComparison { subquery, .. } => subquery,
+            other => panic!("expected a quantified comparison, got {:?}", other),
+        };
+        let operators = [
+            ("=", BinaryOperator::Equal),
+            ("!=", BinaryOperator::NotEqual),
+            ("<", BinaryOperator::Less),
+            ("<=", BinaryOperator::LessEqual),
+            (">", BinaryOperator::Greater),
+            (">=", BinaryOperator::GreaterEqual),
+        ];
+        // SOME is a synonym for ANY.
+        let quantifiers = [("ANY", AnyOrAll::Any), ("SOME", AnyOrAll::Any), ("ALL", AnyOrAll::All)];
+        for (symbol, op) in &operators {
+            for (word, quantifier) in &quantifiers {
+                assert_eq!(
+                    expr(&format!("a {} {} (SELECT b FROM t)", symbol, word)),
+                    Expression::QuantifiedComparison {
+                        left: Box::new(expr("a")),
+                        op: op.clone(),
+                        quantifier: quantifier.clone(),
+                        subquery: subquery.clone(),
+                    }
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn quantifier_needs_a_subquery() {
+        assert_eq!(
+            parse_expression(&tokenize("x = ANY (1, 2)"), 0),
+            Err(ParseError::UnexpectedToken {
+                found: Token::LParen,
+                expected: vec!["'(SELECT ...)' after ANY".to_string()],
+                pos: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn similar_to_and_regex_matches() {
+        let matches = |op| Expression::BinaryOp {
+            left: Box::new(expr("name")),
+            op,
+            right: Box::new(Expression::String("a.*".to_string())),
+        };
+        let cases = [
+            ("SIMILAR TO", BinaryOperator::SimilarTo),
+            ("NOT SIMILAR TO", BinaryOperator::NotSimilarTo),
+            ("~", BinaryOperator::RegexMatch),
+            ("~*", BinaryOperator::RegexMatchCI),
+            ("!~", BinaryOperator::RegexNotMatch),
+            ("!~*", BinaryOperator::RegexNotMatchCI),
+        ];
+        for (operator, op) in cases {
+            assert_eq!(expr(&format!("name {} 'a.*'", operator)), matches(op.clone()));
+            // They bind like comparisons, so AND and OR take the whole match.
+            assert_eq!(
+                expr(&format!("x = 1 AND name {} 'a.*' OR y", operator)),
+                Expression::BinaryOp {
+                    left: Box::new(Expression::BinaryOp {
+                        left: Box::new(expr("x = 1")),
+                        op: BinaryOperator::And,
+                        right: Box::new(matches(op)),
+                    }),
+                    op: BinaryOperator::Or,
+                    right: Box::new(expr("y")),
+                }
+            );
+        }
+        assert!(matches!(
+            parse_expression(&tokenize("name SIMILAR 'a'"), 0),
+            Err(ParseError::UnexpectedToken { pos: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn trim_forms() {
+        let trim = |side, chars: Option<&str>| Expression::Trim {
+            side,
+            chars: chars.map(|c| Box::new(Expression::String(c.to_string()))),
+            expr: Box::new(expr("s")),
+        };
+        assert_eq!(expr("TRIM(LEADING 'x' FROM s)"), trim(TrimSide::Leading, Some("x")));
+        assert_eq!(expr("TRIM(TRAILING 'x' FROM s)"), trim(TrimSide::Trailing, Some("x")));
+        assert_eq!(expr("TRIM('x' FROM s)"), trim(TrimSide::Both, Some("x")));
+        assert_eq!(expr("TRIM(BOTH FROM s)"), trim(TrimSide::Both, None));
+        assert_eq!(expr("TRIM(s)"), trim(TrimSide::Both, None));
+        assert!(matches!(
+            parse_expression(&tokenize("TRIM(LEADING 'x' FROM s"), 0),
+            Err(ParseError::UnexpectedEof { .. })
+        ));
+    }
+}
@@ -0,0 +1,31 @@
+//! Feeds arbitrary bytes through `tokenize` and then `parse`. Either stage may
+//! reject the input, but neither may panic or hang.
+//!
+//! Run from the repository root (needs a nightly toolchain):
+//!
+//! ```text
+//! cargo install cargo-fuzz
+//! cargo +nightly fuzz run tokenize_parse
+//! ```
+//!
+//! Crashing inputs are written to `fuzz/artifacts/tokenize_parse/` and can be
+//! replayed with `cargo +nightly fuzz run tokenize_parse <path>`.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[allow(dead_code)]
+#[path = "../../expression.rs"]
+mod expression;
+#[allow(dead_code)]
+#[path = "../../parser.rs"]
+mod parser;
+#[allow(dead_code)]
+#[path = "../../tokenizer.rs"]
+mod tokenizer;
+
+fuzz_target!(|data: &[u8]| {
+    let input = String::from_utf8_lossy(data);
+    let tokens = tokenizer::tokenize(&input);
+    let _ = parser::parse(&tokens);
+});
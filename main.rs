@@ -1,14 +1,25 @@
-use std::io::{self, Write};
+use std::fs::File;
+use std::io::{self, BufReader, Write};
 
 mod tokenizer;
 mod parser;
 mod expression;
+mod reader;
+mod references;
 
 use tokenizer::tokenize;
-use parser::parse;
-use expression::parse_expression;
+use parser::{parse_statements, parse_statements_recovering};
+use expression::{normalize, parse_expression};
+use reader::StatementReader;
+use references::{referenced_columns, referenced_tables};
 
 fn main() {
+    // Given a file, parse every statement in it instead of prompting.
+    if let Some(path) = std::env::args().nth(1) {
+        parse_file(&path);
+        return;
+    }
+
     println!("Enter a SQL query or expression:");
     print!("> ");
     io::stdout().flush().unwrap();
@@ -27,13 +38,57 @@ fn main() {
 
     println!("\nParsed expression (if any):");
     match parse_expression(&tokens, 0) {
-        Ok((expr, _)) => println!("{:#?}", expr),
+        Ok((expr, _)) => {
+            println!("{:#?}", expr);
+            println!("\nNormalized expression:");
+            println!("{:#?}", normalize(expr));
+        }
         Err(e) => eprintln!("Error parsing expression: {}", e),
     }
 
-    println!("\nParsed statement (if any):");
-    match parse(&tokens) {
-        Ok(stmt) => println!("{:#?}", stmt),
-        Err(e) => eprintln!("Error parsing statement: {}", e),
+    println!("\nParsed statements (if any):");
+    match parse_statements(&tokens) {
+        Ok(statements) => {
+            for stmt in statements {
+                println!("{:#?}", stmt);
+            }
+        }
+        // Parse again without stopping so every broken statement is reported.
+        Err(_) => {
+            let (statements, errors) = parse_statements_recovering(&tokens);
+            for stmt in statements {
+                println!("{:#?}", stmt);
+            }
+            for e in errors {
+                eprintln!("Error parsing statement: {}", e);
+            }
+        }
+    }
+}
+
+fn parse_file(path: &str) {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("Error opening {}: {}", path, e);
+            return;
+        }
+    };
+    for result in StatementReader::new(BufReader::new(file)) {
+        match result {
+            Ok(stmt) => {
+                println!("{:#?}", stmt);
+                println!("Tables: {}", referenced_tables(&stmt).join(", "));
+                println!("Columns: {}", referenced_columns(&stmt).join(", "));
+                if let Some(count) = stmt.output_column_count() {
+                    println!("Output columns: {}", count);
+                }
+                if let Some(table) = stmt.table_name() {
+                    println!("Table: {}", table);
+                }
+                println!("Read-only: {}", stmt.is_read_only());
+            }
+            Err(e) => eprintln!("Error parsing statement: {}", e),
+        }
     }
-}
\ No newline at end of file
+}
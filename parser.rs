@@ -1,299 +1,5312 @@
-use crate::expression::Expression;
-use crate::tokenizer::{Token, Token::*};
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Range;
 
-#[derive(Debug)]
+use crate::expression::{for_each_identifier, CommaSeparated, Expression, MAX_EXPRESSION_DEPTH};
+use crate::tokenizer::{Keyword, Name, Symbol, Token, Token::*};
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum DBType {
     Int,
     Varchar(u64),
+    Char(u64),
     Bool,
+    Uuid,
+    Json,
+    Jsonb,
+    Xml,
+    Bytea,
+    /// Auto-incrementing integer. Like BIGSERIAL and SMALLSERIAL it implies NOT
+    /// NULL and a sequence supplying the default; both are semantic, so the
+    /// parser adds no constraint for them.
+    Serial,
+    BigSerial,
+    SmallSerial,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Constraint {
     PrimaryKey,
     NotNull,
     Check(Expression),
 }
 
-#[derive(Debug)]
+/// Two columns are equal when their definitions are; `token_index` is where the
+/// definition was read from, not part of it, and is ignored.
+#[derive(Debug, Clone)]
 pub struct TableColumn {
-    pub column_name: String,
+    pub column_name: Symbol,
     pub column_type: DBType,
     pub constraints: Vec<Constraint>,
+    /// The text of an inline `COMMENT 'text'` among the constraints.
+    pub comment: Option<String>,
+    /// Index of the column-name token in the parsed token stream, used to
+    /// point errors at the offending column definition.
+    pub token_index: usize,
+}
+
+impl PartialEq for TableColumn {
+    fn eq(&self, other: &Self) -> bool {
+        self.column_name == other.column_name
+            && self.column_type == other.column_type
+            && self.constraints == other.constraints
+            && self.comment == other.comment
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Statement {
     Select {
         columns: Vec<Expression>,
-        from: String,
+        /// The comma-separated FROM list, an implicit cross join of its entries.
+        /// Empty for a SELECT without FROM.
+        from: Vec<TableRef>,
         r#where: Option<Expression>,
-        orderby: Vec<(Expression, Option<Order>)>, // Expression to order by, and ASC/DESC
+        /// The GROUP BY expressions; empty without GROUP BY.
+        group_by: Vec<Expression>,
+        having: Option<Expression>,
+        orderby: Vec<OrderByExpr>,
+        limit: Option<Expression>,
+        offset: Option<Expression>,
+        locking: Option<LockingClause>,
     },
+    /// `left UNION | INTERSECT | EXCEPT [ALL] right`. INTERSECT binds tighter than
+    /// UNION and EXCEPT, which associate to the left. A trailing ORDER BY, LIMIT or
+    /// OFFSET is stored on the root `SetOperation` and sorts the combined result;
+    /// the SELECTs inside, including the last one, are left without them.
+    SetOperation {
+        op: SetOp,
+        all: bool,
+        left: Box<Statement>,
+        right: Box<Statement>,
+        orderby: Vec<OrderByExpr>,
+        limit: Option<Expression>,
+        offset: Option<Expression>,
+    },
+    /// `WITH [RECURSIVE] name [(col, ...)] AS (SELECT ...) [, ...] SELECT ...`: the
+    /// common table expressions are visible to the body and to later CTEs.
+    Query {
+        ctes: Vec<Cte>,
+        recursive: bool,
+        body: Box<Statement>,
+    },
+    /// `VALUES (expr, ...) [, ...]` as a query of literal rows, all of one arity.
+    Values(Vec<Vec<Expression>>),
     CreateTable {
         table_name: String,
+        if_not_exists: bool,
         column_list: Vec<TableColumn>,
+        /// The text of a `COMMENT 'text'` after the column list.
+        comment: Option<String>,
+    },
+    /// `CREATE TABLE name AS SELECT ...`: a table created from a query's result.
+    CreateTableAs {
+        table_name: String,
+        if_not_exists: bool,
+        query: Box<Statement>,
+    },
+    AlterTable {
+        name: String,
+        action: AlterAction,
+    },
+    CreateIndex {
+        name: String,
+        table: String,
+        columns: Vec<IndexColumn>,
+        unique: bool,
+        concurrently: bool,
+        if_not_exists: bool,
+        r#where: Option<Expression>, // Partial index predicate
+    },
+    CreateView {
+        name: String,
+        or_replace: bool,
+        columns: Option<Vec<String>>,
+        query: Box<Statement>,
+        with_check_option: bool,
+    },
+    CreateDatabase {
+        name: String,
+        if_not_exists: bool,
+    },
+    /// `DROP DATABASE [IF EXISTS] name [CASCADE | RESTRICT]`. The CASCADE and
+    /// RESTRICT modifiers are PostgreSQL-specific; RESTRICT is the default.
+    DropDatabase {
+        name: String,
+        if_exists: bool,
+        cascade: bool,
+    },
+    /// `CREATE SCHEMA [IF NOT EXISTS] name [AUTHORIZATION role]`. Without a name,
+    /// `CREATE SCHEMA AUTHORIZATION role` names the schema after its owner.
+    CreateSchema {
+        name: String,
+        if_not_exists: bool,
+        authorization: Option<String>,
+    },
+    /// `DROP SCHEMA [IF EXISTS] name [CASCADE | RESTRICT]`.
+    DropSchema {
+        name: String,
+        if_exists: bool,
+        cascade: bool,
+    },
+    /// `COMMENT ON object IS 'text' | NULL`; `IS NULL` removes the comment.
+    Comment {
+        object: CommentObject,
+        value: Option<String>,
+    },
+    Truncate {
+        tables: Vec<String>,
+        restart_identity: bool,
+        cascade: Option<bool>, // None = not specified, Some(true) = CASCADE, Some(false) = RESTRICT
+    },
+    Explain {
+        analyze: bool,
+        verbose: bool,
+        buffers: bool,
+        format: Option<ExplainFormat>,
+        statement: Box<Statement>,
+    },
+    BeginTransaction {
+        isolation_level: Option<IsolationLevel>,
+    },
+    Commit {
+        chain: bool,
+    },
+    Rollback {
+        chain: bool,
+    },
+    Savepoint {
+        name: String,
+    },
+    /// `DROP INDEX [CONCURRENTLY] [IF EXISTS] name [ON table]`. CONCURRENTLY is
+    /// PostgreSQL-only (drop without locking out writes), while `ON table` is
+    /// MySQL syntax, where index names are scoped to their table.
+    DropIndex {
+        name: String,
+        if_exists: bool,
+        concurrently: bool,
+        on_table: Option<String>,
+    },
+    Show {
+        object: ShowObject,
+    },
+    /// MySQL's `DESCRIBE table`, also spelled `DESC table`.
+    Describe {
+        table: String,
+    },
+    /// `SET [SESSION | GLOBAL | LOCAL] name { = | TO } value [, ...]`. A list of
+    /// values is PostgreSQL's form (`SET search_path = public, myschema`); MySQL's
+    /// `SET @@global.name = ...` is folded into `scope`.
+    Set {
+        variable: String,
+        values: Vec<Expression>,
+        scope: Option<SetScope>,
+    },
+    Grant {
+        privileges: Vec<Privilege>,
+        on: GrantObject,
+        to: Vec<String>,
+        with_grant_option: bool,
+    },
+    Revoke {
+        privileges: Vec<Privilege>,
+        on: GrantObject,
+        from: Vec<String>,
+        cascade: bool,
+    },
+    /// PostgreSQL's bulk transfer: `COPY t [(cols)] FROM ...` or
+    /// `COPY { t | (query) } TO ...`, with an optional `[WITH] (option, ...)`.
+    Copy {
+        table_or_query: CopySource,
+        direction: CopyDirection,
+        options: Vec<CopyOption>,
+    },
+    /// PostgreSQL's `CREATE TYPE name AS ENUM (...) | (field type, ...) | RANGE (...)`.
+    CreateType {
+        name: String,
+        definition: TypeDefinition,
+    },
+    CreateSequence {
+        name: String,
+        if_not_exists: bool,
+        options: SequenceOptions,
+    },
+    /// `MERGE INTO target [[AS] alias] USING source ON condition WHEN ...`.
+    Merge {
+        target: String,
+        target_alias: Option<String>,
+        source: TableRef,
+        on: Expression,
+        clauses: Vec<MergeClause>,
+    },
+}
+
+impl Statement {
+    /// The number of columns a query returns. None for a statement that is not a
+    /// query, and for a select list with `*` or `t.*`, whose width depends on the
+    /// tables.
+    pub fn output_column_count(&self) -> Option<usize> {
+        match self {
+            Statement::Select { columns, .. } => {
+                let has_star = columns.iter().any(|column| match column {
+                    Expression::Identifier(name) => name == "*",
+                    Expression::QualifiedStar(_) => true,
+                    _ => false,
+                });
+                if has_star {
+                    None
+                } else {
+                    Some(columns.len())
+                }
+            }
+            Statement::SetOperation { left, .. } => left.output_column_count(),
+            Statement::Query { body, .. } => body.output_column_count(),
+            Statement::Values(rows) => rows.first().map(Vec::len),
+            _ => None,
+        }
+    }
+
+    /// The table a DDL or data-changing statement acts on: the first one for
+    /// TRUNCATE, the target of MERGE and COPY, and the table of a column comment.
+    /// None for queries and for statements about other objects.
+    pub fn table_name(&self) -> Option<&str> {
+        match self {
+            Statement::CreateTable { table_name: table, .. }
+            | Statement::CreateTableAs { table_name: table, .. }
+            | Statement::AlterTable { name: table, .. }
+            | Statement::CreateIndex { table, .. }
+            | Statement::Describe { table }
+            | Statement::Merge { target: table, .. }
+            | Statement::Copy {
+                table_or_query: CopySource::Table(table, _),
+                ..
+            }
+            | Statement::Comment {
+                object: CommentObject::Table(table) | CommentObject::Column(table, _),
+                ..
+            } => Some(table),
+            Statement::DropIndex { on_table, .. } => on_table.as_deref(),
+            Statement::Truncate { tables, .. } => tables.first().map(String::as_str),
+            _ => None,
+        }
+    }
+
+    /// Whether the statement leaves the database unchanged. Queries are
+    /// read-only unless they lock rows (`FOR UPDATE`); EXPLAIN ANALYZE runs its
+    /// statement, so it is read-only only if that statement is. Transaction
+    /// control and SET affect just the session and count as read-only.
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            Statement::Select { locking, .. } => locking.is_none(),
+            Statement::SetOperation { left, right, .. } => left.is_read_only() && right.is_read_only(),
+            Statement::Query { ctes, body, .. } => {
+                ctes.iter().all(|cte| cte.query.is_read_only()) && body.is_read_only()
+            }
+            Statement::Explain {
+                analyze, statement, ..
+            } => !analyze || statement.is_read_only(),
+            Statement::Copy { direction, .. } => matches!(direction, CopyDirection::To(_)),
+            Statement::Values(_)
+            | Statement::Show { .. }
+            | Statement::Describe { .. }
+            | Statement::BeginTransaction { .. }
+            | Statement::Commit { .. }
+            | Statement::Rollback { .. }
+            | Statement::Savepoint { .. }
+            | Statement::Set { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+/// One `name [(col, ...)] AS (SELECT ...)` entry of a WITH clause.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cte {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub query: Box<Statement>,
+}
+
+/// What an `ALTER TABLE name ...` statement changes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlterAction {
+    /// `RENAME TO new_name`
+    RenameTable(String),
+    /// `RENAME [COLUMN] old TO new`; COLUMN is optional in MySQL.
+    RenameColumn { from: String, to: String },
+    /// `ADD [CONSTRAINT name] PRIMARY KEY (col, ...) | CHECK (expr)`
+    AddConstraint(TableConstraint),
+    /// `DROP CONSTRAINT name [CASCADE | RESTRICT]`
+    DropConstraint { name: String, cascade: bool },
+    /// `ALTER [COLUMN] name action`
+    AlterColumn { name: String, action: ColumnAlteration },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnAlteration {
+    /// `[SET DATA] TYPE type`
+    SetType(DBType),
+    SetDefault(Expression),
+    DropDefault,
+    SetNotNull,
+    DropNotNull,
+}
+
+/// A constraint declared on the table rather than on one column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableConstraint {
+    pub name: Option<String>,
+    pub constraint: Constraint,
+    /// The columns of a `PRIMARY KEY (col, ...)`; empty for CHECK.
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexColumn {
+    pub expr: Expression,
+    pub direction: Option<Order>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExplainFormat {
+    Text,
+    Json,
+    Xml,
+    Yaml,
+}
+
+/// What a `SHOW` statement lists. The table-scoped forms carry the table name.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShowObject {
+    Tables,
+    Databases,
+    Columns(String),
+    Indexes(String),
+    CreateTable(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    All,
+    /// `UPDATE (col, ...)`: the privilege is limited to the listed columns.
+    UpdateColumns(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GrantObject {
+    Table(String),
+    AllTablesInSchema(String),
+    Schema(String),
+    Database(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeDefinition {
+    /// The labels of an enum type, in declaration order.
+    Enum(Vec<String>),
+    /// The fields of a composite type, declared like table columns.
+    Composite(Vec<TableColumn>),
+    Range { subtype: String },
+}
+
+/// Options of CREATE SEQUENCE; `None` leaves the database default.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SequenceOptions {
+    pub start: Option<i64>,
+    pub increment: Option<i64>,
+    pub min_value: Option<i64>,
+    pub max_value: Option<i64>,
+    pub cache: Option<i64>,
+    /// `Some(false)` for an explicit `NO CYCLE`.
+    pub cycle: Option<bool>,
+    /// The `table.column` from `OWNED BY`; `OWNED BY NONE` leaves it `None`.
+    pub owned_by: Option<String>,
+}
+
+/// One `WHEN ... [AND condition] THEN action` arm of a MERGE.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeClause {
+    Matched(Option<Expression>, MergeAction),
+    /// `WHEN NOT MATCHED [BY TARGET]`: a source row with no target row.
+    NotMatchedByTarget(Option<Expression>, MergeAction),
+    /// `WHEN NOT MATCHED BY SOURCE`: a target row with no source row.
+    NotMatchedBySource(Option<Expression>, MergeAction),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeAction {
+    Update { assignments: Vec<(String, Expression)> },
+    Insert { columns: Vec<String>, values: Vec<Expression> },
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CopySource {
+    /// A table with an optional column list.
+    Table(String, Option<Vec<String>>),
+    Query(Box<Statement>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CopyDirection {
+    From(CopyTarget),
+    To(CopyTarget),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CopyTarget {
+    Stdin,
+    Stdout,
+    Filename(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CopyOption {
+    /// `FORMAT text | csv | binary`, kept as written.
+    Format(String),
+    Header(bool),
+    Delimiter(String),
+    Escape(String),
+    Null(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetScope {
+    Session,
+    Global,
+    Local,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableRef {
+    Table {
+        name: String,
+        alias: Option<String>,
+        tablesample: Option<TableSample>,
     },
+    /// A parenthesized SELECT in FROM; the alias is mandatory. A LATERAL
+    /// subquery may refer to tables listed before it in the same FROM.
+    Derived {
+        query: Box<Statement>,
+        alias: String,
+        lateral: bool,
+    },
+}
+
+/// `TABLESAMPLE method(percent) [REPEATABLE(seed)]` after a table in FROM.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableSample {
+    pub method: String,
+    pub percent: Expression,
+    pub seed: Option<Expression>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Order {
     Asc,
     Desc,
 }
 
-pub fn parse(tokens: &[Token]) -> Result<Statement, String> {
-    let mut iter = tokens.iter().enumerate().peekable();
+#[derive(Debug, Clone, PartialEq)]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommentObject {
+    Table(String),
+    /// Table and column, split from `table.column`.
+    Column(String, String),
+    Database(String),
+    Schema(String),
+    Index(String),
+    View(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetOp {
+    Union,
+    Intersect,
+    Except,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderByExpr {
+    pub expr: Expression,
+    pub direction: Option<Order>,
+    pub nulls: Option<NullsOrder>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockingClause {
+    pub strength: LockStrength,
+    pub of_tables: Vec<String>,
+    pub wait: LockWait,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LockStrength {
+    Update,
+    NoKeyUpdate,
+    Share,
+    KeyShare,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LockWait {
+    Wait,
+    NoWait,
+    SkipLocked,
+}
 
-    match iter.next() {
-        Some((_, Keyword(k))) if k == "SELECT" => parse_select_statement(&mut iter, tokens),
-        Some((_, Keyword(k))) if k == "CREATE" => parse_create_table_statement(&mut iter, tokens),
-        _ => Err("Unsupported or invalid SQL statement".into()),
+// Writes the ORDER BY, LIMIT and OFFSET that end a SELECT or set operation.
+fn write_query_tail(
+    f: &mut fmt::Formatter<'_>,
+    orderby: &[OrderByExpr],
+    limit: &Option<Expression>,
+    offset: &Option<Expression>,
+) -> fmt::Result {
+    if !orderby.is_empty() {
+        write!(f, " ORDER BY {}", CommaSeparated(orderby))?;
     }
+    if let Some(limit) = limit {
+        write!(f, " LIMIT {}", limit)?;
+    }
+    if let Some(offset) = offset {
+        write!(f, " OFFSET {}", offset)?;
+    }
+    Ok(())
 }
 
-fn parse_select_statement<'a, I>(iter: &mut std::iter::Peekable<I>, all_tokens: &'a [Token]) -> Result<Statement, String>
-where
-    I: Iterator<Item = (usize, &'a Token)>,
-{
-    let mut columns = vec![];
-    loop {
-        match iter.next() {
-            Some((_, Identifier(name))) => columns.push(Expression::Identifier(name.to_string())),
-            Some((_, StringLiteral(s))) => columns.push(Expression::String(s.to_string())),
-            Some((_, Number(n))) => columns.push(Expression::Number(*n)),
-            Some((_, BoolLiteral(b))) => columns.push(Expression::Bool(*b)),
-            Some((_, Star)) => columns.push(Expression::Identifier("*".to_string())),
-            Some((_, LParen)) => {
-                let mut paren_level = 1;
-                let mut inner_tokens = vec![LParen];
-                let mut inner_count = 0;
-                while let Some((_, token)) = iter.next() {
-                    inner_tokens.push(token.clone());
-                    inner_count += 1;
-                    match token {
-                        LParen => paren_level += 1,
-                        RParen => paren_level -= 1,
-                        EOF => return Err("Unclosed parenthesis".into()),
-                        _ => {}
+// Formats a list of names separated by commas, quoting those that need it.
+struct Names<'a>(&'a [String]);
+
+impl fmt::Display for Names<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<Name> = self.0.iter().map(|name| Name(name)).collect();
+        write!(f, "{}", CommaSeparated(&names))
+    }
+}
+
+/// Formats the statement as SQL that parses back to an equal statement. Optional
+/// spellings are normalized: `START TRANSACTION` prints as `BEGIN`, `LIMIT o, n`
+/// as `LIMIT n OFFSET o`, and a bare `CHAR` as `CHAR(1)`.
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let if_not_exists = |yes: bool| if yes { "IF NOT EXISTS " } else { "" };
+        let if_exists = |yes: bool| if yes { "IF EXISTS " } else { "" };
+        let cascade = |yes: bool| if yes { " CASCADE" } else { "" };
+        match self {
+            Statement::Select {
+                columns,
+                from,
+                r#where,
+                group_by,
+                having,
+                orderby,
+                limit,
+                offset,
+                locking,
+            } => {
+                f.write_str("SELECT")?;
+                if !columns.is_empty() {
+                    write!(f, " {}", CommaSeparated(columns))?;
+                }
+                if !from.is_empty() {
+                    write!(f, " FROM {}", CommaSeparated(from))?;
+                }
+                if let Some(condition) = r#where {
+                    write!(f, " WHERE {}", condition)?;
+                }
+                if !group_by.is_empty() {
+                    write!(f, " GROUP BY {}", CommaSeparated(group_by))?;
+                }
+                if let Some(condition) = having {
+                    write!(f, " HAVING {}", condition)?;
+                }
+                write_query_tail(f, orderby, limit, offset)?;
+                if let Some(locking) = locking {
+                    write!(f, " {}", locking)?;
+                }
+                Ok(())
+            }
+            Statement::SetOperation {
+                op,
+                all,
+                left,
+                right,
+                orderby,
+                limit,
+                offset,
+            } => {
+                let all = if *all { " ALL" } else { "" };
+                write!(f, "{} {}{} {}", left, op, all, right)?;
+                write_query_tail(f, orderby, limit, offset)
+            }
+            Statement::Query { ctes, recursive, body } => {
+                let recursive = if *recursive { "RECURSIVE " } else { "" };
+                write!(f, "WITH {}{} {}", recursive, CommaSeparated(ctes), body)
+            }
+            Statement::Values(rows) => {
+                f.write_str("VALUES ")?;
+                for (i, row) in rows.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
                     }
-                    if paren_level == 0 {
-                        break;
+                    write!(f, "({})", CommaSeparated(row))?;
+                }
+                Ok(())
+            }
+            Statement::CreateTable {
+                table_name,
+                if_not_exists: exists,
+                column_list,
+                comment,
+            } => {
+                write!(f, "CREATE TABLE {}{} ({})", if_not_exists(*exists), Name(table_name), CommaSeparated(column_list))?;
+                if let Some(comment) = comment {
+                    write!(f, " COMMENT '{}'", comment)?;
+                }
+                Ok(())
+            }
+            Statement::CreateTableAs {
+                table_name,
+                if_not_exists: exists,
+                query,
+            } => write!(f, "CREATE TABLE {}{} AS {}", if_not_exists(*exists), Name(table_name), query),
+            Statement::AlterTable { name, action } => write!(f, "ALTER TABLE {} {}", Name(name), action),
+            Statement::CreateIndex {
+                name,
+                table,
+                columns,
+                unique,
+                concurrently,
+                if_not_exists: exists,
+                r#where,
+            } => {
+                let unique = if *unique { "UNIQUE " } else { "" };
+                let concurrently = if *concurrently { "CONCURRENTLY " } else { "" };
+                write!(
+                    f,
+                    "CREATE {}INDEX {}{}{} ON {} ({})",
+                    unique,
+                    concurrently,
+                    if_not_exists(*exists),
+                    Name(name),
+                    Name(table),
+                    CommaSeparated(columns)
+                )?;
+                if let Some(condition) = r#where {
+                    write!(f, " WHERE {}", condition)?;
+                }
+                Ok(())
+            }
+            Statement::CreateView {
+                name,
+                or_replace,
+                columns,
+                query,
+                with_check_option,
+            } => {
+                let or_replace = if *or_replace { "OR REPLACE " } else { "" };
+                write!(f, "CREATE {}VIEW {}", or_replace, Name(name))?;
+                if let Some(columns) = columns {
+                    write!(f, " ({})", Names(columns))?;
+                }
+                write!(f, " AS {}", query)?;
+                if *with_check_option {
+                    f.write_str(" WITH CHECK OPTION")?;
+                }
+                Ok(())
+            }
+            Statement::CreateDatabase {
+                name,
+                if_not_exists: exists,
+            } => write!(f, "CREATE DATABASE {}{}", if_not_exists(*exists), Name(name)),
+            Statement::DropDatabase {
+                name,
+                if_exists: exists,
+                cascade: drop_cascade,
+            } => write!(f, "DROP DATABASE {}{}{}", if_exists(*exists), Name(name), cascade(*drop_cascade)),
+            Statement::CreateSchema {
+                name,
+                if_not_exists: exists,
+                authorization,
+            } => {
+                write!(f, "CREATE SCHEMA {}{}", if_not_exists(*exists), Name(name))?;
+                if let Some(role) = authorization {
+                    write!(f, " AUTHORIZATION {}", Name(role))?;
+                }
+                Ok(())
+            }
+            Statement::DropSchema {
+                name,
+                if_exists: exists,
+                cascade: drop_cascade,
+            } => write!(f, "DROP SCHEMA {}{}{}", if_exists(*exists), Name(name), cascade(*drop_cascade)),
+            Statement::Comment { object, value } => {
+                write!(f, "COMMENT ON {} IS ", object)?;
+                match value {
+                    Some(text) => write!(f, "'{}'", text),
+                    None => f.write_str("NULL"),
+                }
+            }
+            Statement::Truncate {
+                tables,
+                restart_identity,
+                cascade: behavior,
+            } => {
+                write!(f, "TRUNCATE TABLE {}", Names(tables))?;
+                if *restart_identity {
+                    f.write_str(" RESTART IDENTITY")?;
+                }
+                match behavior {
+                    Some(true) => f.write_str(" CASCADE"),
+                    Some(false) => f.write_str(" RESTRICT"),
+                    None => Ok(()),
+                }
+            }
+            Statement::Explain {
+                analyze,
+                verbose,
+                buffers,
+                format,
+                statement,
+            } => {
+                f.write_str("EXPLAIN ")?;
+                // BUFFERS and FORMAT only exist in the option list.
+                if *buffers || format.is_some() {
+                    let mut options = Vec::new();
+                    if *analyze {
+                        options.push("ANALYZE".to_string());
+                    }
+                    if *verbose {
+                        options.push("VERBOSE".to_string());
+                    }
+                    if *buffers {
+                        options.push("BUFFERS".to_string());
+                    }
+                    if let Some(format) = format {
+                        options.push(format!("FORMAT {}", format));
+                    }
+                    write!(f, "({}) ", options.join(", "))?;
+                } else {
+                    if *analyze {
+                        f.write_str("ANALYZE ")?;
+                    }
+                    if *verbose {
+                        f.write_str("VERBOSE ")?;
                     }
                 }
-                let (expr, _) = crate::expression::parse_expression(&inner_tokens[1..inner_tokens.len() - 1], 0)
-                    .map_err(|e| format!("Error parsing expression in parentheses: {}", e))?;
-                columns.push(expr);
+                write!(f, "{}", statement)
+            }
+            Statement::BeginTransaction { isolation_level } => {
+                f.write_str("BEGIN")?;
+                if let Some(level) = isolation_level {
+                    write!(f, " ISOLATION LEVEL {}", level)?;
+                }
+                Ok(())
+            }
+            Statement::Commit { chain } => write!(f, "COMMIT{}", if *chain { " AND CHAIN" } else { "" }),
+            Statement::Rollback { chain } => write!(f, "ROLLBACK{}", if *chain { " AND CHAIN" } else { "" }),
+            Statement::Savepoint { name } => write!(f, "SAVEPOINT {}", Name(name)),
+            Statement::DropIndex {
+                name,
+                if_exists: exists,
+                concurrently,
+                on_table,
+            } => {
+                let concurrently = if *concurrently { "CONCURRENTLY " } else { "" };
+                write!(f, "DROP INDEX {}{}{}", concurrently, if_exists(*exists), Name(name))?;
+                if let Some(table) = on_table {
+                    write!(f, " ON {}", Name(table))?;
+                }
+                Ok(())
+            }
+            Statement::Show { object } => write!(f, "SHOW {}", object),
+            Statement::Describe { table } => write!(f, "DESCRIBE {}", Name(table)),
+            Statement::Set { variable, values, scope } => {
+                f.write_str("SET ")?;
+                if let Some(scope) = scope {
+                    write!(f, "{} ", scope)?;
+                }
+                write!(f, "{} = {}", Name(variable), CommaSeparated(values))
+            }
+            Statement::Grant {
+                privileges,
+                on,
+                to,
+                with_grant_option,
+            } => {
+                write!(f, "GRANT {} ON {} TO {}", CommaSeparated(privileges), on, Names(to))?;
+                if *with_grant_option {
+                    f.write_str(" WITH GRANT OPTION")?;
+                }
+                Ok(())
+            }
+            Statement::Revoke {
+                privileges,
+                on,
+                from,
+                cascade: revoke_cascade,
+            } => write!(
+                f,
+                "REVOKE {} ON {} FROM {}{}",
+                CommaSeparated(privileges),
+                on,
+                Names(from),
+                cascade(*revoke_cascade)
+            ),
+            Statement::Copy {
+                table_or_query,
+                direction,
+                options,
+            } => {
+                match table_or_query {
+                    CopySource::Table(table, columns) => {
+                        write!(f, "COPY {}", Name(table))?;
+                        if let Some(columns) = columns {
+                            write!(f, " ({})", Names(columns))?;
+                        }
+                    }
+                    CopySource::Query(query) => write!(f, "COPY ({})", query)?,
+                }
+                match direction {
+                    CopyDirection::From(target) => write!(f, " FROM {}", target)?,
+                    CopyDirection::To(target) => write!(f, " TO {}", target)?,
+                }
+                if !options.is_empty() {
+                    write!(f, " ({})", CommaSeparated(options))?;
+                }
+                Ok(())
+            }
+            Statement::CreateType { name, definition } => write!(f, "CREATE TYPE {} AS {}", Name(name), definition),
+            Statement::CreateSequence {
+                name,
+                if_not_exists: exists,
+                options,
+            } => write!(f, "CREATE SEQUENCE {}{}{}", if_not_exists(*exists), Name(name), options),
+            Statement::Merge {
+                target,
+                target_alias,
+                source,
+                on,
+                clauses,
+            } => {
+                write!(f, "MERGE INTO {}", Name(target))?;
+                if let Some(alias) = target_alias {
+                    write!(f, " AS {}", Name(alias))?;
+                }
+                write!(f, " USING {} ON {}", source, on)?;
+                for clause in clauses {
+                    write!(f, " {}", clause)?;
+                }
+                Ok(())
             }
-            Some((_, Comma)) => continue,
-            Some((_, Keyword(k))) if k == "FROM" => break,
-            Some((_, token)) => return Err(format!("Unexpected token in SELECT columns: {:?}", token)),
-            None => return Err("Expected FROM clause".into()),
         }
     }
+}
 
-    let from = match iter.next() {
-        Some((_, Identifier(name))) => name.to_string(),
-        Some((_, token)) => return Err(format!("Expected table name after FROM, got: {:?}", token)),
-        None => return Err("Expected table name after FROM".into()),
-    };
+impl fmt::Display for DBType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DBType::Int => f.write_str("INT"),
+            DBType::Varchar(len) => write!(f, "VARCHAR({})", len),
+            DBType::Char(len) => write!(f, "CHAR({})", len),
+            DBType::Bool => f.write_str("BOOL"),
+            DBType::Uuid => f.write_str("UUID"),
+            DBType::Json => f.write_str("JSON"),
+            DBType::Jsonb => f.write_str("JSONB"),
+            DBType::Xml => f.write_str("XML"),
+            DBType::Bytea => f.write_str("BYTEA"),
+            DBType::Serial => f.write_str("SERIAL"),
+            DBType::BigSerial => f.write_str("BIGSERIAL"),
+            DBType::SmallSerial => f.write_str("SMALLSERIAL"),
+        }
+    }
+}
 
-    let mut r#where = None;
-    if let Some((_, Keyword(k))) = iter.peek() {
-        if k == "WHERE" {
-            iter.next(); // Consume WHERE
-            let start_index = iter.peek().map_or(all_tokens.len(), |(idx, _)| *idx);
-            let remaining_slice = &all_tokens[start_index..];
-            let (expr, consumed) = crate::expression::parse_expression(remaining_slice, 0)
-                .map_err(|e| format!("Error parsing WHERE clause: {}", e))?;
-            r#where = Some(expr);
-            for _ in 0..consumed {
-                if iter.next().is_none() {
-                    break;
-                }
-            }
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constraint::PrimaryKey => f.write_str("PRIMARY KEY"),
+            Constraint::NotNull => f.write_str("NOT NULL"),
+            Constraint::Check(condition) => write!(f, "CHECK ({})", condition),
         }
     }
+}
 
-    let mut orderby = vec![];
-    if let Some((_, Keyword(k))) = iter.peek() {
-        if k == "ORDER" {
-            iter.next(); // Consume ORDER
-            if let Some((_, Keyword(by_k))) = iter.next() {
-                if by_k == "BY" {
-                    loop {
-                        let start_index = iter.peek().map_or(all_tokens.len(), |(idx, _)| *idx);
-                        let remaining_slice = &all_tokens[start_index..];
-                        let (expr, consumed) = crate::expression::parse_expression(remaining_slice, 0)
-                            .map_err(|e| format!("Error parsing ORDER BY expression: {}", e))?;
-                        let order = match iter.peek() {
-                            Some((_, Asc)) => {
-                                iter.next();
-                                Some(Order::Asc)
-                            }
-                            Some((_, Desc)) => {
-                                iter.next();
-                                Some(Order::Desc)
-                            }
-                            _ => None,
-                        };
-                        orderby.push((expr, order));
-                        for _ in 0..consumed {
-                            if iter.next().is_none() {
-                                break;
-                            }
-                        }
-                        if let Some((_, Comma)) = iter.peek() {
-                            iter.next(); // Consume comma
-                        } else {
-                            break;
-                        }
-                    }
-                } else {
-                    return Err("Expected BY after ORDER".into());
-                }
-            } else {
-                return Err("Expected BY after ORDER".into());
-            }
+impl fmt::Display for TableColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", Name(&self.column_name), self.column_type)?;
+        for constraint in &self.constraints {
+            write!(f, " {}", constraint)?;
+        }
+        if let Some(comment) = &self.comment {
+            write!(f, " COMMENT '{}'", comment)?;
         }
+        Ok(())
     }
+}
 
-    Ok(Statement::Select {
-        columns,
-        from,
-        r#where,
-        orderby,
-    })
+impl fmt::Display for Cte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Name(&self.name))?;
+        if !self.columns.is_empty() {
+            write!(f, " ({})", Names(&self.columns))?;
+        }
+        write!(f, " AS ({})", self.query)
+    }
 }
 
-fn parse_create_table_statement<'a, I>(iter: &mut std::iter::Peekable<I>, all_tokens: &'a [Token]) -> Result<Statement, String>
-where
-    I: Iterator<Item = (usize, &'a Token)>,
-{
-    if let Some((_, Keyword(k))) = iter.next() {
-        if k == "TABLE" {
-            if let Some((_, Identifier(name))) = iter.next() {
-                if let Some((_, LParen)) = iter.next() {
-                    let mut column_list = Vec::new();
-                    loop {
-                        if let Some((_, RParen)) = iter.peek() {
-                            iter.next();
-                            break;
-                        }
-                        if let Some((_, Identifier(col_name))) = iter.next() {
-                            let column = parse_table_column(col_name.to_string(), iter, all_tokens)?;
-                            column_list.push(column);
-                            if let Some((_, Comma)) = iter.peek() {
-                                iter.next();
-                            } else if let Some((_, RParen)) = iter.peek() {
-                                continue;
-                            } else {
-                                return Err("Expected comma or closing parenthesis after column definition".into());
-                            }
-                        } else {
-                            return Err("Expected column name".into());
-                        }
-                    }
-                    return Ok(Statement::CreateTable {
-                        table_name: name.to_string(),
-                        column_list,
-                    });
-                } else {
-                    return Err("Expected opening parenthesis after table name".into());
-                }
-            } else {
-                return Err("Expected table name after CREATE TABLE".into());
+impl fmt::Display for AlterAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlterAction::RenameTable(name) => write!(f, "RENAME TO {}", Name(name)),
+            AlterAction::RenameColumn { from, to } => write!(f, "RENAME COLUMN {} TO {}", Name(from), Name(to)),
+            AlterAction::AddConstraint(constraint) => write!(f, "ADD {}", constraint),
+            AlterAction::DropConstraint { name, cascade } => {
+                write!(f, "DROP CONSTRAINT {}{}", Name(name), if *cascade { " CASCADE" } else { "" })
             }
-        } else {
-            return Err("Expected TABLE after CREATE".into());
+            AlterAction::AlterColumn { name, action } => write!(f, "ALTER COLUMN {} {}", Name(name), action),
         }
-    } else {
-        return Err("Expected TABLE keyword".into());
     }
 }
 
-fn parse_table_column<'a, I>(
-    column_name: String,
-    iter: &mut std::iter::Peekable<I>,
-    all_tokens: &'a [Token],
-) -> Result<TableColumn, String>
-where
-    I: Iterator<Item = (usize, &'a Token)>,
-{
-    let column_type = match iter.next() {
-        Some((_, Int)) => DBType::Int,
-        Some((_, Varchar(len))) => DBType::Varchar(*len),
-        Some((_, Bool)) => DBType::Bool,
-        Some((_, token)) => return Err(format!("Unexpected data type: {:?}", token)),
-        None => return Err("Expected data type".into()),
-    };
+impl fmt::Display for ColumnAlteration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnAlteration::SetType(column_type) => write!(f, "TYPE {}", column_type),
+            ColumnAlteration::SetDefault(value) => write!(f, "SET DEFAULT {}", value),
+            ColumnAlteration::DropDefault => f.write_str("DROP DEFAULT"),
+            ColumnAlteration::SetNotNull => f.write_str("SET NOT NULL"),
+            ColumnAlteration::DropNotNull => f.write_str("DROP NOT NULL"),
+        }
+    }
+}
 
-    let mut constraints = Vec::new();
-    while let Some((_, token)) = iter.peek() {
-        match token {
-            PrimaryKey => {
-                constraints.push(Constraint::PrimaryKey);
-                iter.next();
-                if let Some((_, Keyword(k))) = iter.peek() {
-                    if k == "KEY" {
-                        iter.next();
-                    }
-                }
-            }
-            Keyword(k) if k == "NOT" => {
-                iter.next();
-                if let Some((_, Keyword(null_k))) = iter.next() {
-                    if null_k == "NULL" {
-                        constraints.push(Constraint::NotNull);
-                    } else {
-                        return Err("Expected NULL after NOT".into());
-                    }
-                } else {
-                    return Err("Expected NULL after NOT".into());
-                }
-            }
-            Check => {
-                iter.next();
-                if let Some((_, LParen)) = iter.next() {
-                    let start_index = iter.peek().map_or(all_tokens.len(), |(idx, _)| *idx);
-                    let remaining_slice = &all_tokens[start_index..];
-                    let (expr, consumed) = crate::expression::parse_expression(remaining_slice, 0)
-                        .map_err(|e| format!("Error parsing CHECK expression: {}", e))?;
-                    constraints.push(Constraint::Check(expr));
-                    for _ in 0..consumed {
-                        if iter.next().is_none() {
-                            break;
-                        }
-                    }
-                    if let Some((_, RParen)) = iter.next() {
-                    } else {
-                        return Err("Expected closing parenthesis after CHECK expression".into());
-                    }
-                } else {
-                    return Err("Expected opening parenthesis after CHECK".into());
-                }
-            }
-            Comma | RParen => break,
-            _ => break, // Stop if it's not a constraint keyword
+impl fmt::Display for TableConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "CONSTRAINT {} ", Name(name))?;
+        }
+        match &self.constraint {
+            Constraint::PrimaryKey => write!(f, "PRIMARY KEY ({})", Names(&self.columns)),
+            constraint => write!(f, "{}", constraint),
         }
     }
+}
 
-    Ok(TableColumn {
-        column_name,
-        column_type,
-        constraints,
-    })
+impl fmt::Display for IndexColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)?;
+        if let Some(direction) = &self.direction {
+            write!(f, " {}", direction)?;
+        }
+        Ok(())
+    }
 }
 
-// Helper function to convert the rest of the iterator into a Vec of tokens
-fn tokens_from_iterator<'a, I>(iter: &mut std::iter::Peekable<I>) -> Vec<&'a Token>
-where
-    I: Iterator<Item = &'a Token>,
-{
-    let mut tokens = Vec::new();
-    while let Some(token) = iter.peek() {
-        tokens.push(*token); // Dereference token
-        iter.next();
+impl fmt::Display for ExplainFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExplainFormat::Text => "TEXT",
+            ExplainFormat::Json => "JSON",
+            ExplainFormat::Xml => "XML",
+            ExplainFormat::Yaml => "YAML",
+        })
     }
-    tokens
-}
\ No newline at end of file
+}
+
+impl fmt::Display for ShowObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShowObject::Tables => f.write_str("TABLES"),
+            ShowObject::Databases => f.write_str("DATABASES"),
+            ShowObject::Columns(table) => write!(f, "COLUMNS FROM {}", Name(table)),
+            ShowObject::Indexes(table) => write!(f, "INDEXES FROM {}", Name(table)),
+            ShowObject::CreateTable(table) => write!(f, "CREATE TABLE {}", Name(table)),
+        }
+    }
+}
+
+impl fmt::Display for Privilege {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Privilege::Select => f.write_str("SELECT"),
+            Privilege::Insert => f.write_str("INSERT"),
+            Privilege::Update => f.write_str("UPDATE"),
+            Privilege::Delete => f.write_str("DELETE"),
+            Privilege::All => f.write_str("ALL PRIVILEGES"),
+            Privilege::UpdateColumns(columns) => write!(f, "UPDATE ({})", Names(columns)),
+        }
+    }
+}
+
+impl fmt::Display for GrantObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrantObject::Table(name) => write!(f, "TABLE {}", Name(name)),
+            GrantObject::AllTablesInSchema(schema) => write!(f, "ALL TABLES IN SCHEMA {}", Name(schema)),
+            GrantObject::Schema(name) => write!(f, "SCHEMA {}", Name(name)),
+            GrantObject::Database(name) => write!(f, "DATABASE {}", Name(name)),
+        }
+    }
+}
+
+impl fmt::Display for TypeDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeDefinition::Enum(labels) => {
+                let labels: Vec<String> = labels.iter().map(|label| format!("'{}'", label)).collect();
+                write!(f, "ENUM ({})", labels.join(", "))
+            }
+            TypeDefinition::Composite(fields) => write!(f, "({})", CommaSeparated(fields)),
+            TypeDefinition::Range { subtype } => write!(f, "RANGE (SUBTYPE = {})", subtype),
+        }
+    }
+}
+
+// Formats the options with a leading space each, as they follow the sequence name.
+impl fmt::Display for SequenceOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = [
+            ("START WITH", self.start),
+            ("INCREMENT BY", self.increment),
+            ("MINVALUE", self.min_value),
+            ("MAXVALUE", self.max_value),
+            ("CACHE", self.cache),
+        ];
+        for (option, value) in values {
+            if let Some(value) = value {
+                write!(f, " {} {}", option, value)?;
+            }
+        }
+        match self.cycle {
+            Some(true) => f.write_str(" CYCLE")?,
+            Some(false) => f.write_str(" NO CYCLE")?,
+            None => {}
+        }
+        if let Some(column) = &self.owned_by {
+            write!(f, " OWNED BY {}", Name(column))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MergeClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (when, condition, action) = match self {
+            MergeClause::Matched(condition, action) => ("MATCHED", condition, action),
+            MergeClause::NotMatchedByTarget(condition, action) => ("NOT MATCHED", condition, action),
+            MergeClause::NotMatchedBySource(condition, action) => ("NOT MATCHED BY SOURCE", condition, action),
+        };
+        write!(f, "WHEN {}", when)?;
+        if let Some(condition) = condition {
+            write!(f, " AND {}", condition)?;
+        }
+        write!(f, " THEN {}", action)
+    }
+}
+
+impl fmt::Display for MergeAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeAction::Update { assignments } => {
+                f.write_str("UPDATE SET ")?;
+                for (i, (column, value)) in assignments.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{} = {}", Name(column), value)?;
+                }
+                Ok(())
+            }
+            MergeAction::Insert { columns, values } => {
+                f.write_str("INSERT ")?;
+                if !columns.is_empty() {
+                    write!(f, "({}) ", Names(columns))?;
+                }
+                write!(f, "VALUES ({})", CommaSeparated(values))
+            }
+            MergeAction::Delete => f.write_str("DELETE"),
+        }
+    }
+}
+
+impl fmt::Display for CopyTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CopyTarget::Stdin => f.write_str("STDIN"),
+            CopyTarget::Stdout => f.write_str("STDOUT"),
+            CopyTarget::Filename(path) => write!(f, "'{}'", path),
+        }
+    }
+}
+
+impl fmt::Display for CopyOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CopyOption::Format(name) => write!(f, "FORMAT {}", Name(name)),
+            CopyOption::Header(true) => f.write_str("HEADER TRUE"),
+            CopyOption::Header(false) => f.write_str("HEADER FALSE"),
+            CopyOption::Delimiter(value) => write!(f, "DELIMITER '{}'", value),
+            CopyOption::Escape(value) => write!(f, "ESCAPE '{}'", value),
+            CopyOption::Null(value) => write!(f, "NULL '{}'", value),
+        }
+    }
+}
+
+impl fmt::Display for SetScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SetScope::Session => "SESSION",
+            SetScope::Global => "GLOBAL",
+            SetScope::Local => "LOCAL",
+        })
+    }
+}
+
+impl fmt::Display for IsolationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        })
+    }
+}
+
+impl fmt::Display for TableRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableRef::Table {
+                name,
+                alias,
+                tablesample,
+            } => {
+                write!(f, "{}", Name(name))?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {}", Name(alias))?;
+                }
+                if let Some(sample) = tablesample {
+                    write!(f, " TABLESAMPLE {} ({})", sample.method, sample.percent)?;
+                    if let Some(seed) = &sample.seed {
+                        write!(f, " REPEATABLE ({})", seed)?;
+                    }
+                }
+                Ok(())
+            }
+            TableRef::Derived { query, alias, lateral } => {
+                let lateral = if *lateral { "LATERAL " } else { "" };
+                write!(f, "{}({}) AS {}", lateral, query, Name(alias))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Order::Asc => "ASC",
+            Order::Desc => "DESC",
+        })
+    }
+}
+
+impl fmt::Display for CommentObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommentObject::Table(name) => write!(f, "TABLE {}", Name(name)),
+            CommentObject::Column(table, column) => write!(f, "COLUMN {}.{}", Name(table), Name(column)),
+            CommentObject::Database(name) => write!(f, "DATABASE {}", Name(name)),
+            CommentObject::Schema(name) => write!(f, "SCHEMA {}", Name(name)),
+            CommentObject::Index(name) => write!(f, "INDEX {}", Name(name)),
+            CommentObject::View(name) => write!(f, "VIEW {}", Name(name)),
+        }
+    }
+}
+
+impl fmt::Display for SetOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SetOp::Union => "UNION",
+            SetOp::Intersect => "INTERSECT",
+            SetOp::Except => "EXCEPT",
+        })
+    }
+}
+
+impl fmt::Display for OrderByExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)?;
+        if let Some(direction) = &self.direction {
+            write!(f, " {}", direction)?;
+        }
+        match self.nulls {
+            Some(NullsOrder::First) => f.write_str(" NULLS FIRST"),
+            Some(NullsOrder::Last) => f.write_str(" NULLS LAST"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for LockingClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.strength {
+            LockStrength::Update => "FOR UPDATE",
+            LockStrength::NoKeyUpdate => "FOR NO KEY UPDATE",
+            LockStrength::Share => "FOR SHARE",
+            LockStrength::KeyShare => "FOR KEY SHARE",
+        })?;
+        if !self.of_tables.is_empty() {
+            write!(f, " OF {}", Names(&self.of_tables))?;
+        }
+        match self.wait {
+            LockWait::Wait => Ok(()),
+            LockWait::NoWait => f.write_str(" NOWAIT"),
+            LockWait::SkipLocked => f.write_str(" SKIP LOCKED"),
+        }
+    }
+}
+
+/// Dialect and strictness toggles for the parser.
+#[derive(Debug, Clone)]
+pub struct ParserOptions {
+    /// Require exactly one comma between SELECT columns. When false, stray,
+    /// doubled and missing commas in the column list are tolerated, though a
+    /// name right after a column is always that column's alias.
+    pub strict_commas: bool,
+    /// Accept a SELECT with no FROM clause (`SELECT 1 + 1`). The column list
+    /// then ends at a semicolon, the end of input or a clause such as LIMIT,
+    /// and `from` is empty; WHERE and ORDER BY are still rejected without a FROM.
+    pub allow_no_from: bool,
+    /// Length given to a `VARCHAR` column declared without an explicit length.
+    pub default_varchar_len: u64,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            strict_commas: false,
+            allow_no_from: true,
+            default_varchar_len: 255,
+        }
+    }
+}
+
+/// Why a statement or expression failed to parse. `Display` gives the message
+/// shown to users. Token positions count from the start of the tokens passed
+/// to the parser, with comment tokens left out.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// `found`, at index `pos`, where one of `expected` was required.
+    UnexpectedToken {
+        found: Token,
+        expected: Vec<String>,
+        pos: usize,
+    },
+    /// The input ended where one of `expected` was required.
+    UnexpectedEof { expected: Vec<String> },
+    /// An expression that is wrong as a whole rather than at one token, such as
+    /// one nested too deeply. `pos` is where it starts.
+    InvalidExpression { reason: String, pos: usize },
+    /// `error` occurred inside the part of the input that `context` names, as in
+    /// "Error parsing WHERE clause: ...".
+    Context {
+        context: String,
+        error: Box<ParseError>,
+    },
+    /// `error` occurred in statement `index` of a script, counting non-empty
+    /// statements from 0, which spans the tokens in `span`.
+    InStatement {
+        index: usize,
+        span: Range<usize>,
+        error: Box<ParseError>,
+    },
+    /// The column list of CREATE TABLE `table` is malformed as a whole rather than
+    /// at one unexpected token; `pos` is where `problem` shows.
+    MalformedColumnList {
+        table: String,
+        problem: ColumnListProblem,
+        pos: usize,
+    },
+    /// The reserved word `word` at `pos` where a name was required. Quoted, as
+    /// in `"order"`, it would be a name.
+    ReservedWord {
+        word: String,
+        expected: String,
+        pos: usize,
+    },
+    /// The name `word` at `pos` where a keyword was required, and it looks
+    /// like a misspelling of `suggestion`.
+    Misspelled {
+        word: String,
+        suggestion: Keyword,
+        pos: usize,
+    },
+}
+
+/// How a CREATE TABLE column list is malformed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnListProblem {
+    /// `()`, with `pos` at the closing parenthesis.
+    Empty,
+    /// `(a INT,)`, with `pos` at the closing parenthesis after the comma.
+    TrailingComma,
+    /// The input ends inside the list, with `pos` at its opening parenthesis.
+    Unclosed,
+}
+
+impl ParseError {
+    /// `found` at `pos` instead of `expected`; the EOF token counts as the end of input.
+    pub(crate) fn unexpected(found: &Token, pos: usize, expected: &str) -> Self {
+        match found {
+            Eof => ParseError::eof(expected),
+            _ => ParseError::UnexpectedToken {
+                found: found.clone(),
+                expected: vec![expected.to_string()],
+                pos,
+            },
+        }
+    }
+
+    /// `found` at `pos` instead of the name `expected`; a reserved word gets an
+    /// error that suggests quoting it.
+    pub(crate) fn expected_name(found: &Token, pos: usize, expected: &str) -> Self {
+        match reserved_word(found) {
+            Some(word) => ParseError::ReservedWord {
+                word,
+                expected: expected.to_string(),
+                pos,
+            },
+            None => ParseError::unexpected(found, pos, expected),
+        }
+    }
+
+    pub(crate) fn eof(expected: &str) -> Self {
+        ParseError::UnexpectedEof {
+            expected: vec![expected.to_string()],
+        }
+    }
+
+    pub(crate) fn context(self, context: impl Into<String>) -> Self {
+        ParseError::Context {
+            context: context.into(),
+            error: Box::new(self),
+        }
+    }
+
+    fn in_statement(self, index: usize, span: Range<usize>) -> Self {
+        ParseError::InStatement {
+            index,
+            span,
+            error: Box::new(self),
+        }
+    }
+
+    /// Shifts token positions by `by`, for an error from a parser that was given
+    /// the tokens starting at index `by`.
+    pub(crate) fn offset(self, by: usize) -> Self {
+        match self {
+            ParseError::UnexpectedToken { found, expected, pos } => ParseError::UnexpectedToken {
+                found,
+                expected,
+                pos: pos + by,
+            },
+            ParseError::InvalidExpression { reason, pos } => ParseError::InvalidExpression { reason, pos: pos + by },
+            ParseError::MalformedColumnList { table, problem, pos } => ParseError::MalformedColumnList {
+                table,
+                problem,
+                pos: pos + by,
+            },
+            ParseError::ReservedWord { word, expected, pos } => ParseError::ReservedWord {
+                word,
+                expected,
+                pos: pos + by,
+            },
+            ParseError::Misspelled { word, suggestion, pos } => ParseError::Misspelled {
+                word,
+                suggestion,
+                pos: pos + by,
+            },
+            ParseError::Context { context, error } => ParseError::Context {
+                context,
+                error: Box::new(error.offset(by)),
+            },
+            ParseError::InStatement { index, span, error } => ParseError::InStatement {
+                index,
+                span: span.start + by..span.end + by,
+                error: Box::new(error.offset(by)),
+            },
+            other => other,
+        }
+    }
+}
+
+// The spelling of a token that is a word the tokenizer reserves, such as ORDER,
+// KEY or CHECK. None for names, symbols and literals.
+pub(crate) fn reserved_word(token: &Token) -> Option<String> {
+    if matches!(token, Identifier(_)) {
+        return None;
+    }
+    let text = token.to_string();
+    (!text.is_empty() && text.chars().all(|c| c.is_ascii_alphabetic())).then_some(text)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedToken { found, expected, pos } => {
+                write!(f, "Expected {} at token {}, got: '{}'", expected.join(" or "), pos, found)
+            }
+            ParseError::UnexpectedEof { expected } => write!(f, "Expected {} at end of input", expected.join(" or ")),
+            ParseError::InvalidExpression { reason, .. } => f.write_str(reason),
+            ParseError::MalformedColumnList { table, problem, pos } => match problem {
+                ColumnListProblem::Empty => {
+                    write!(f, "Table '{}' must have at least one column, got ')' at token {}", table, pos)
+                }
+                ColumnListProblem::TrailingComma => {
+                    write!(f, "Trailing comma before ')' at token {} in the columns of table '{}'", pos, table)
+                }
+                ColumnListProblem::Unclosed => {
+                    write!(f, "Columns of table '{}' opened at token {} are never closed with ')'", table, pos)
+                }
+            },
+            ParseError::ReservedWord { word, expected, pos } => write!(
+                f,
+                "Expected {}, got reserved word {} at token {}; quote it as \"{}\" to use it as a name",
+                expected,
+                word,
+                pos,
+                word.to_lowercase()
+            ),
+            ParseError::Misspelled { word, suggestion, pos } => {
+                write!(f, "Unexpected '{}' at token {}; did you mean {}?", word, pos, suggestion)
+            }
+            ParseError::Context { context, error } => write!(f, "{}: {}", context, error),
+            ParseError::InStatement { index, span, error } => {
+                write!(f, "Error in statement {} (tokens {}..{}): {}", index + 1, span.start, span.end, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Context { error, .. } | ParseError::InStatement { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse(tokens: &[Token]) -> Result<Statement, ParseError> {
+    parse_with_options(tokens, &ParserOptions::default())
+}
+
+// Comment tokens (see `TokenizerOptions::keep_comments`) are dropped before
+// parsing, so token indices in errors count only the remaining tokens.
+pub fn parse_with_options(tokens: &[Token], options: &ParserOptions) -> Result<Statement, ParseError> {
+    let tokens = without_comments(tokens);
+    let mut stream = TokenStream::new(&tokens);
+    let statement = parse_statement(&mut stream, options)?;
+    expect_end_of_statement(&mut stream)?;
+    Ok(statement)
+}
+
+/// Parses a script of semicolon-separated statements, stopping at the first one
+/// that fails. The error is a [`ParseError::InStatement`] holding that
+/// statement's index among the non-empty statements and its token range. Empty
+/// statements (`;;`) are skipped, so an empty script gives an empty list.
+pub fn parse_statements(tokens: &[Token]) -> Result<Vec<Statement>, ParseError> {
+    let tokens = without_comments(tokens);
+    let options = ParserOptions::default();
+    let mut statements = Vec::new();
+    for (index, range) in statement_ranges(&tokens).into_iter().enumerate() {
+        let mut stream = TokenStream::new(&tokens[..range.end]);
+        stream.seek(range.start);
+        let statement = parse_statement(&mut stream, &options)
+            .and_then(|statement| expect_end_of_statement(&mut stream).map(|_| statement))
+            .map_err(|e| e.in_statement(index, range))?;
+        statements.push(statement);
+    }
+    Ok(statements)
+}
+
+/// Parses a script like [`parse_statements`], but an error does not stop it:
+/// parsing resumes after the failed statement's semicolon, so every broken
+/// statement is reported. Returns the statements that parsed and the errors,
+/// each in script order.
+pub fn parse_statements_recovering(tokens: &[Token]) -> (Vec<Statement>, Vec<ParseError>) {
+    let (statements, errors): (Vec<_>, Vec<_>) = parse_program_recovering(tokens).into_iter().partition(Result::is_ok);
+    (
+        statements.into_iter().map(Result::unwrap).collect(),
+        errors.into_iter().map(Result::unwrap_err).collect(),
+    )
+}
+
+/// Parses a script of semicolon-separated statements, returning one result per
+/// statement. A statement that fails to parse does not stop the others: parsing
+/// resumes after its semicolon. Empty statements (`;;`) are skipped, token
+/// indices in errors count from the start of the script, and each error names
+/// its statement like those of [`parse_statements`].
+pub fn parse_program_recovering(tokens: &[Token]) -> Vec<Result<Statement, ParseError>> {
+    let tokens = without_comments(tokens);
+    let options = ParserOptions::default();
+    statement_ranges(&tokens)
+        .into_iter()
+        .enumerate()
+        .map(|(index, range)| {
+            // The stream ends at the statement's semicolon, so a statement that
+            // fails midway cannot read into the next one.
+            let mut stream = TokenStream::new(&tokens[..range.end]);
+            stream.seek(range.start);
+            parse_statement(&mut stream, &options)
+                .and_then(|statement| expect_end_of_statement(&mut stream).map(|_| statement))
+                .map_err(|e| e.in_statement(index, range))
+        })
+        .collect()
+}
+
+// A statement may be followed by semicolons, but by nothing else before the end
+// of input.
+fn expect_end_of_statement<'a>(stream: &mut TokenStream<'a>) -> Result<(), ParseError> {
+    while let Some((_, Semicolon)) = stream.peek() {
+        stream.next();
+    }
+    match stream.peek() {
+        Some((_, Eof)) | None => Ok(()),
+        Some((index, token)) => {
+            // A misspelled clause keyword is read as a name, so it is either the
+            // stray token itself (`WHERE x = 1 ODER BY x`) or the alias just before
+            // it (`FROM t WEHRE x = 1`).
+            let suggestion = [Some((index, token)), index.checked_sub(1).and_then(|i| Some((i, stream.tokens.get(i)?)))]
+                .into_iter()
+                .flatten()
+                .find_map(|(pos, token)| match token {
+                    Identifier(word) => suggest_keyword(word, CLAUSE_KEYWORDS).map(|suggestion| ParseError::Misspelled {
+                        word: word.to_string(),
+                        suggestion,
+                        pos,
+                    }),
+                    _ => None,
+                });
+            Err(suggestion.unwrap_or_else(|| ParseError::unexpected(token, index, "end of statement")))
+        }
+    }
+}
+
+// The keywords that start a statement and the clauses that follow a FROM list,
+// the candidates when a misspelled one has been read as a name.
+const STATEMENT_KEYWORDS: &[Keyword] = &[
+    Keyword::Select, Keyword::With, Keyword::Create, Keyword::Drop, Keyword::Truncate,
+    Keyword::Alter, Keyword::Begin, Keyword::Start, Keyword::Commit, Keyword::Rollback,
+    Keyword::Savepoint, Keyword::Explain, Keyword::Show, Keyword::Describe, Keyword::Set,
+    Keyword::Grant, Keyword::Revoke, Keyword::Copy, Keyword::Merge, Keyword::Values,
+];
+const CLAUSE_KEYWORDS: &[Keyword] = &[
+    Keyword::From, Keyword::Where, Keyword::Group, Keyword::Having, Keyword::Order, Keyword::Limit,
+    Keyword::Offset, Keyword::Union, Keyword::Intersect, Keyword::Except,
+];
+
+/// The keyword among `candidates` that `word` is most likely a misspelling of.
+/// Words shorter than three letters get no suggestion, and a keyword must be
+/// within two edits of the word and closer than half its length.
+fn suggest_keyword(word: &str, candidates: &[Keyword]) -> Option<Keyword> {
+    let word = word.to_ascii_uppercase();
+    if word.len() < 3 {
+        return None;
+    }
+    candidates
+        .iter()
+        .map(|&keyword| (edit_distance(&word, keyword.as_str()), keyword))
+        .filter(|&(distance, _)| distance > 0 && distance <= 2 && distance * 2 <= word.len())
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, keyword)| keyword)
+}
+
+// The Levenshtein distance between two ASCII words.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, &ca) in a.as_bytes().iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// The token ranges of the non-empty statements in `tokens`, each including the
+// `;` or EOF that ends it.
+fn statement_ranges(tokens: &[Token]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        if matches!(token, Semicolon | Eof) {
+            if index > start {
+                ranges.push(start..index + 1);
+            }
+            start = index + 1;
+        }
+    }
+    if start < tokens.len() {
+        ranges.push(start..tokens.len());
+    }
+    ranges
+}
+
+fn without_comments(tokens: &[Token]) -> Cow<'_, [Token]> {
+    if tokens.iter().any(|token| matches!(token, Comment(..))) {
+        Cow::Owned(tokens.iter().filter(|token| !matches!(token, Comment(..))).cloned().collect())
+    } else {
+        Cow::Borrowed(tokens)
+    }
+}
+
+// A nested query takes about twice the stack of one level of expression
+// nesting, so it counts double against `MAX_EXPRESSION_DEPTH`.
+const QUERY_NESTING_COST: usize = 2;
+
+/// A cursor over the token slice, shared by every statement parser. Expressions
+/// are parsed from `remaining()` and the cursor is then advanced past them, so
+/// each token is visited once.
+pub(crate) struct TokenStream<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    // How deeply the query being parsed is nested. Expressions start counting
+    // their own nesting from here, so both share `MAX_EXPRESSION_DEPTH`.
+    depth: usize,
+}
+
+impl<'a> TokenStream<'a> {
+    pub(crate) fn new(tokens: &'a [Token]) -> Self {
+        TokenStream { tokens, pos: 0, depth: 0 }
+    }
+
+    /// The token at the cursor with its index, without consuming it.
+    fn peek(&self) -> Option<(usize, &'a Token)> {
+        self.tokens.get(self.pos).map(|token| (self.pos, token))
+    }
+
+    /// The index of the next unconsumed token.
+    fn index(&self) -> usize {
+        self.pos
+    }
+
+    /// The tokens from the cursor to the end of input.
+    fn remaining(&self) -> &'a [Token] {
+        &self.tokens[self.pos..]
+    }
+
+    /// Moves the cursor back to an already-consumed token at index `pos`.
+    fn seek(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
+    /// Skips `count` tokens, stopping at the end of input.
+    fn advance(&mut self, count: usize) {
+        self.pos = (self.pos + count).min(self.tokens.len());
+    }
+}
+
+impl<'a> Iterator for TokenStream<'a> {
+    type Item = (usize, &'a Token);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.peek()?;
+        self.pos += 1;
+        Some(item)
+    }
+}
+
+// Dispatches on the leading keyword of a statement.
+fn parse_statement<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+) -> Result<Statement, ParseError> {
+    match stream.next() {
+        Some((_, Keyword(Keyword::Select))) => parse_select_statement(stream, options),
+        Some((_, Keyword(Keyword::With))) => parse_with_statement(stream, options),
+        Some((_, Keyword(Keyword::Create))) => match stream.peek() {
+            Some((_, Keyword(Keyword::Unique | Keyword::Index))) => parse_create_index_statement(stream, options),
+            // OR is tokenized as the boolean operator; here it can only start OR REPLACE.
+            Some((_, Operator(op))) if op == "OR" => parse_create_view_statement(stream, options),
+            Some((_, Keyword(Keyword::View))) => parse_create_view_statement(stream, options),
+            Some((_, Keyword(Keyword::Database))) => parse_create_database_statement(stream),
+            Some((_, Keyword(Keyword::Sequence))) => parse_create_sequence_statement(stream),
+            Some((_, Keyword(Keyword::Schema))) => parse_create_schema_statement(stream),
+            // TYPE is not reserved, so it is matched by name.
+            Some((_, Identifier(word))) if word.eq_ignore_ascii_case("TYPE") => parse_create_type_statement(stream, options),
+            _ => parse_create_table_statement(stream, options),
+        },
+        Some((_, Keyword(Keyword::Drop))) => match stream.next() {
+            Some((_, Keyword(Keyword::Index))) => parse_drop_index_statement(stream),
+            Some((_, Keyword(Keyword::Database))) => parse_drop_database_statement(stream),
+            Some((_, Keyword(Keyword::Schema))) => parse_drop_schema_statement(stream),
+            Some((pos, token)) => Err(ParseError::unexpected(token, pos, "INDEX, DATABASE or SCHEMA after DROP")),
+            None => Err(ParseError::eof("INDEX, DATABASE or SCHEMA after DROP")),
+        },
+        Some((_, Keyword(Keyword::Truncate))) => parse_truncate_statement(stream),
+        Some((_, Keyword(Keyword::Alter))) => parse_alter_table_statement(stream, options),
+        Some((_, Keyword(Keyword::Begin))) => parse_begin_statement(stream),
+        // The SQL-standard spelling of BEGIN.
+        Some((_, Keyword(Keyword::Start))) => match stream.next() {
+            Some((_, Keyword(Keyword::Transaction))) => parse_transaction_modes(stream),
+            Some((pos, token)) => Err(ParseError::unexpected(token, pos, "TRANSACTION after START")),
+            None => Err(ParseError::eof("TRANSACTION after START")),
+        },
+        Some((_, Keyword(Keyword::Commit))) => {
+            skip_work_or_transaction(stream);
+            Ok(Statement::Commit {
+                chain: parse_and_chain(stream)?,
+            })
+        }
+        Some((_, Keyword(Keyword::Rollback))) => {
+            skip_work_or_transaction(stream);
+            Ok(Statement::Rollback {
+                chain: parse_and_chain(stream)?,
+            })
+        }
+        Some((_, Keyword(Keyword::Savepoint))) => match stream.next() {
+            Some((_, Identifier(name))) => Ok(Statement::Savepoint { name: name.to_string() }),
+            Some((pos, token)) => Err(ParseError::unexpected(token, pos, "savepoint name")),
+            None => Err(ParseError::eof("savepoint name")),
+        },
+        Some((_, Keyword(Keyword::Explain))) => parse_explain_statement(stream, options),
+        Some((_, Keyword(Keyword::Show))) => parse_show_statement(stream),
+        // DESC only means DESCRIBE at the start of a statement; elsewhere it is
+        // the ORDER BY direction.
+        Some((_, Keyword(Keyword::Describe))) | Some((_, Desc)) => Ok(Statement::Describe {
+            table: parse_object_name(stream, "table")?,
+        }),
+        Some((_, Keyword(Keyword::Set))) => parse_set_statement(stream, options),
+        Some((_, Keyword(Keyword::Grant))) => parse_grant_statement(stream),
+        Some((_, Keyword(Keyword::Revoke))) => parse_revoke_statement(stream),
+        Some((_, Keyword(Keyword::Copy))) => parse_copy_statement(stream, options),
+        Some((_, Keyword(Keyword::Merge))) => parse_merge_statement(stream, options),
+        // COMMENT is not reserved; it starts a statement when ON follows it.
+        Some((_, Identifier(word)))
+            if word.eq_ignore_ascii_case("COMMENT") && matches!(stream.peek(), Some((_, Keyword(Keyword::On)))) =>
+        {
+            parse_comment_statement(stream)
+        }
+        Some((_, Keyword(Keyword::Values))) => Ok(Statement::Values(parse_values_rows(stream, options)?)),
+        Some((pos, token @ Identifier(word))) => match suggest_keyword(word, STATEMENT_KEYWORDS) {
+            Some(suggestion) => Err(ParseError::Misspelled {
+                word: word.to_string(),
+                suggestion,
+                pos,
+            }),
+            None => Err(ParseError::unexpected(token, pos, "a SQL statement")),
+        },
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, "a SQL statement")),
+        None => Err(ParseError::eof("a SQL statement")),
+    }
+}
+
+// Parses the common table expressions after WITH and the SELECT they prefix.
+fn parse_with_statement<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<Statement, ParseError> {
+    let recursive = matches!(stream.peek(), Some((_, Keyword(Keyword::Recursive))));
+    if recursive {
+        stream.next();
+    }
+    let mut ctes = Vec::new();
+    loop {
+        ctes.push(parse_cte(stream, options)?);
+        match stream.peek() {
+            Some((_, Comma)) => {
+                stream.next();
+            }
+            _ => break,
+        }
+    }
+    let body = match stream.next() {
+        Some((_, Keyword(Keyword::Select))) => parse_select_statement(stream, options)?,
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "SELECT after WITH clause")),
+        None => return Err(ParseError::eof("SELECT after WITH clause")),
+    };
+    Ok(Statement::Query {
+        ctes,
+        recursive,
+        body: Box::new(body),
+    })
+}
+
+// Parses `name [(col, ...)] AS (SELECT ...)`.
+fn parse_cte<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<Cte, ParseError> {
+    let name = parse_object_name(stream, "common table expression")?;
+    let mut columns = Vec::new();
+    if let Some((_, LParen)) = stream.peek() {
+        stream.next();
+        loop {
+            columns.push(parse_object_name(stream, "column")?);
+            match stream.next() {
+                Some((_, Comma)) => {}
+                Some((_, RParen)) => break,
+                Some((pos, token)) => {
+                    return Err(ParseError::unexpected(token, pos, &format!("',' or ')' in column list of '{}'", name)))
+                }
+                None => return Err(ParseError::eof(&format!("',' or ')' in column list of '{}'", name))),
+            }
+        }
+    }
+    let expected = format!("AS (...) after common table expression '{}'", name);
+    expect_tokens(stream, &[Keyword(Keyword::As), LParen], &expected)?;
+    let expected = format!("SELECT as the body of common table expression '{}'", name);
+    match stream.next() {
+        Some((_, Keyword(Keyword::Select))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, &expected)),
+        None => return Err(ParseError::eof(&expected)),
+    }
+    let query = parse_select_statement(stream, options)?;
+    let expected = format!("')' after the body of '{}'", name);
+    match stream.next() {
+        Some((_, RParen)) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, &expected)),
+        None => return Err(ParseError::eof(&expected)),
+    }
+    Ok(Cte {
+        name,
+        columns,
+        query: Box::new(query),
+    })
+}
+
+// Parses a SELECT, and any UNION / INTERSECT / EXCEPT chained after it, up to
+// the ORDER BY, LIMIT, OFFSET and FOR clauses that end the whole query.
+fn parse_select_statement<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+) -> Result<Statement, ParseError> {
+    let first = parse_select_body(stream, options)?;
+    let query = parse_set_operations(stream, options, first, false)?;
+    parse_query_tail(stream, options, query)
+}
+
+// Folds the set operations following `left` into a tree. With `intersect_only`
+// it stops at UNION or EXCEPT, which is how the right operand of those picks up
+// the INTERSECTs that bind tighter.
+fn parse_set_operations<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+    mut left: Statement,
+    intersect_only: bool,
+) -> Result<Statement, ParseError> {
+    loop {
+        let (op, keyword) = match stream.peek() {
+            Some((_, Keyword(k @ Keyword::Intersect))) => (SetOp::Intersect, k),
+            Some((_, Keyword(k @ Keyword::Union))) if !intersect_only => (SetOp::Union, k),
+            Some((_, Keyword(k @ Keyword::Except))) if !intersect_only => (SetOp::Except, k),
+            _ => return Ok(left),
+        };
+        stream.next();
+        // ALL is not reserved, so it is matched by name.
+        let all = match stream.peek() {
+            Some((_, Identifier(word))) if word.eq_ignore_ascii_case("ALL") => {
+                stream.next();
+                true
+            }
+            Some((_, Keyword(Keyword::Distinct))) => {
+                stream.next();
+                false
+            }
+            _ => false,
+        };
+        match stream.next() {
+            Some((_, Keyword(Keyword::Select))) => {}
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, &format!("SELECT after {}", keyword))),
+            None => return Err(ParseError::eof(&format!("SELECT after {}", keyword))),
+        }
+        let mut right = parse_select_body(stream, options)?;
+        if op != SetOp::Intersect {
+            right = parse_set_operations(stream, options, right, true)?;
+        }
+        left = Statement::SetOperation {
+            op,
+            all,
+            left: Box::new(left),
+            right: Box::new(right),
+            orderby: vec![],
+            limit: None,
+            offset: None,
+        };
+    }
+}
+
+// Parses a single SELECT up to and including GROUP BY and HAVING.
+fn parse_select_body<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+) -> Result<Statement, ParseError> {
+    let mut columns = vec![];
+    let mut expecting_column = true;
+    let mut last_comma = 0;
+    let mut has_from = true;
+    loop {
+        if options.allow_no_from {
+            // Without FROM the column list runs to the end of the statement, or to
+            // the closing parenthesis when this SELECT is a subquery, or to a set operator.
+            if let Some((_, Eof))
+            | Some((_, Semicolon))
+            | Some((_, RParen))
+            | Some((_, Keyword(Keyword::Union | Keyword::Intersect | Keyword::Except)))
+            | None = stream.peek()
+            {
+                has_from = false;
+                break;
+            }
+        }
+        if !columns.is_empty() {
+            if let Some((pos, token, keyword)) = clause_after_columns(stream) {
+                match keyword {
+                    Keyword::Where => return Err(ParseError::unexpected(token, pos, "FROM clause before WHERE")),
+                    Keyword::Order => return Err(ParseError::unexpected(token, pos, "FROM clause before ORDER BY")),
+                    _ if options.allow_no_from => {
+                        has_from = false;
+                        break;
+                    }
+                    _ => return Err(ParseError::unexpected(token, pos, "FROM clause")),
+                }
+            }
+        }
+        let start = stream.index();
+        let column = match stream.next() {
+            // `t.*`: the dot is folded into the identifier, so the star follows `t.`.
+            Some((_, Identifier(name))) if name.ends_with('.') && matches!(stream.peek(), Some((_, Star))) => {
+                stream.next();
+                Expression::QualifiedStar(name.trim_end_matches('.').to_string())
+            }
+            Some((_, Star)) => Expression::Identifier(Symbol::from("*")),
+            // Anything that can start an expression is parsed as a whole one from
+            // its first token, so operators after a name, literal or parenthesis
+            // belong to the column.
+            Some((
+                index,
+                Identifier(_)
+                | StringLiteral(_)
+                | Number(_)
+                | BoolLiteral(_)
+                | Placeholder(_)
+                | LParen
+                | Keyword(Keyword::Case | Keyword::Exists | Keyword::Not | Keyword::Unknown),
+            )) => {
+                let column = parse_column_expression(stream, options, index, "SELECT column")?;
+                if !expecting_column {
+                    // Without a comma before it, a name like FORM is a misspelled FROM
+                    // rather than another column.
+                    if let Expression::Identifier(name) = &column {
+                        if let Some(suggestion) = suggest_keyword(name, &[Keyword::From]) {
+                            return Err(ParseError::Misspelled {
+                                word: name.to_string(),
+                                suggestion,
+                                pos: index,
+                            });
+                        }
+                    }
+                }
+                parse_column_alias(stream, column)?
+            }
+            Some((pos, Comma)) => {
+                if options.strict_commas && expecting_column {
+                    return Err(ParseError::unexpected(&Comma, pos, "SELECT column"));
+                }
+                expecting_column = true;
+                last_comma = pos;
+                continue;
+            }
+            Some((_, Keyword(Keyword::From))) => break,
+            Some((pos, token)) if reserved_word(token).is_some() => {
+                return Err(ParseError::expected_name(token, pos, "SELECT column"))
+            }
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "SELECT column")),
+            None => return Err(ParseError::eof("FROM clause")),
+        };
+        if options.strict_commas && !expecting_column {
+            return Err(ParseError::unexpected(&stream.tokens[start], start, "',' between SELECT columns"));
+        }
+        columns.push(column);
+        expecting_column = false;
+    }
+    if options.strict_commas && expecting_column && !columns.is_empty() {
+        return Err(ParseError::unexpected(&Comma, last_comma, "SELECT column"));
+    }
+
+    let mut from = vec![];
+    if has_from {
+        from.push(parse_table_ref(stream, options)?);
+        while let Some((_, Comma)) = stream.peek() {
+            stream.next(); // Consume comma
+            from.push(parse_table_ref(stream, options)?);
+        }
+    }
+
+    let mut r#where = None;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Where {
+            stream.next(); // Consume WHERE
+            r#where = Some(parse_clause_expression(stream, options, "WHERE clause")?);
+        }
+    }
+
+    let mut group_by = vec![];
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Group {
+            stream.next(); // Consume GROUP
+            match stream.next() {
+                Some((_, Keyword(Keyword::By))) => {}
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "BY after GROUP")),
+                None => return Err(ParseError::eof("BY after GROUP")),
+            }
+            loop {
+                group_by.push(parse_clause_expression(stream, options, "GROUP BY expression")?);
+                if let Some((_, Comma)) = stream.peek() {
+                    stream.next(); // Consume comma
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+
+    // HAVING is allowed without GROUP BY: the whole input is then one group.
+    let mut having = None;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Having {
+            stream.next(); // Consume HAVING
+            having = Some(parse_clause_expression(stream, options, "HAVING clause")?);
+        }
+    }
+    if let Some((pos, token @ Keyword(Keyword::Group))) = stream.peek() {
+        return Err(ParseError::unexpected(token, pos, "GROUP BY before HAVING"));
+    }
+
+    Ok(Statement::Select {
+        columns,
+        from,
+        r#where,
+        group_by,
+        having,
+        orderby: vec![],
+        limit: None,
+        offset: None,
+        locking: None,
+    })
+}
+
+// The clause keyword at the cursor that ends a SELECT column list in place of
+// FROM, with its position. ORDER and GROUP only count when BY follows them, so
+// a column misnamed `order` gets the reserved-word error instead.
+fn clause_after_columns<'a>(stream: &TokenStream<'a>) -> Option<(usize, &'a Token, Keyword)> {
+    let (pos, token) = stream.peek()?;
+    let keyword = match token {
+        Keyword(k @ (Keyword::Where | Keyword::Having | Keyword::Limit | Keyword::Offset | Keyword::For)) => *k,
+        Keyword(k @ (Keyword::Order | Keyword::Group)) if stream.remaining().get(1) == Some(&Keyword(Keyword::By)) => *k,
+        _ => return None,
+    };
+    Some((pos, token, keyword))
+}
+
+// Parses the ORDER BY, LIMIT / OFFSET and FOR clauses that close a query and
+// attaches them to it; for a set operation they apply to the combined result.
+fn parse_query_tail<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+    mut query: Statement,
+) -> Result<Statement, ParseError> {
+    let mut orderby = vec![];
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Order {
+            stream.next(); // Consume ORDER
+            match stream.next() {
+                Some((_, Keyword(Keyword::By))) => {}
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "BY after ORDER")),
+                None => return Err(ParseError::eof("BY after ORDER")),
+            }
+            loop {
+                let expr_pos = stream.index();
+                let expr = parse_clause_expression(stream, options, "ORDER BY expression")?;
+                validate_order_by_ordinal(first_select_columns(&query), &expr, expr_pos)?;
+                let direction = parse_order_direction(stream);
+                let mut nulls = None;
+                if let Some((_, Keyword(k))) = stream.peek() {
+                    if *k == Keyword::Nulls {
+                        stream.next(); // Consume NULLS
+                        // FIRST and LAST are only words here, so columns may use them.
+                        nulls = match stream.next() {
+                            Some((_, Identifier(word))) if word.eq_ignore_ascii_case("FIRST") => Some(NullsOrder::First),
+                            Some((_, Identifier(word))) if word.eq_ignore_ascii_case("LAST") => Some(NullsOrder::Last),
+                            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "FIRST or LAST after NULLS")),
+                            None => return Err(ParseError::eof("FIRST or LAST after NULLS")),
+                        };
+                    }
+                }
+                orderby.push(OrderByExpr { expr, direction, nulls });
+                if let Some((_, Comma)) = stream.peek() {
+                    stream.next(); // Consume comma
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+
+    if let Some((pos, token @ Keyword(Keyword::Having))) = stream.peek() {
+        return Err(ParseError::unexpected(token, pos, "end of query; HAVING must come before ORDER BY"));
+    }
+
+    let mut limit = None;
+    let mut offset = None;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Limit {
+            stream.next(); // Consume LIMIT
+            let first = parse_limit_value(stream, options, "LIMIT")?;
+            if let Some((_, Comma)) = stream.peek() {
+                // MySQL shorthand: LIMIT offset, count
+                stream.next();
+                offset = Some(first);
+                limit = Some(parse_limit_value(stream, options, "LIMIT")?);
+            } else {
+                limit = Some(first);
+            }
+        }
+    }
+    if offset.is_none() {
+        if let Some((_, Keyword(k))) = stream.peek() {
+            if *k == Keyword::Offset {
+                stream.next(); // Consume OFFSET
+                offset = Some(parse_limit_value(stream, options, "OFFSET")?);
+            }
+        }
+    }
+
+    let mut locking = None;
+    let locking_pos = stream.index();
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::For {
+            stream.next(); // Consume FOR
+            locking = Some(parse_locking_clause(stream)?);
+        }
+    }
+
+    if let Some((pos, token @ Keyword(Keyword::Union | Keyword::Intersect | Keyword::Except))) = stream.peek() {
+        // ORDER BY, LIMIT, OFFSET and FOR must follow the last SELECT.
+        return Err(ParseError::unexpected(token, pos, "end of query after ORDER BY, LIMIT, OFFSET or FOR"));
+    }
+
+    match &mut query {
+        Statement::Select {
+            orderby: select_orderby,
+            limit: select_limit,
+            offset: select_offset,
+            locking: select_locking,
+            ..
+        } => {
+            *select_orderby = orderby;
+            *select_limit = limit;
+            *select_offset = offset;
+            *select_locking = locking;
+        }
+        Statement::SetOperation {
+            orderby: set_orderby,
+            limit: set_limit,
+            offset: set_offset,
+            ..
+        } => {
+            if locking.is_some() {
+                return Err(ParseError::unexpected(
+                    &Keyword(Keyword::For),
+                    locking_pos,
+                    "a single SELECT before FOR UPDATE or FOR SHARE",
+                ));
+            }
+            *set_orderby = orderby;
+            *set_limit = limit;
+            *set_offset = offset;
+        }
+        _ => unreachable!("query tail is only parsed after a SELECT"),
+    }
+    Ok(query)
+}
+
+// The select list that names the columns of a query's result: for a set
+// operation, that of its leftmost SELECT.
+fn first_select_columns(query: &Statement) -> &[Expression] {
+    match query {
+        Statement::SetOperation { left, .. } => first_select_columns(left),
+        Statement::Select { columns, .. } => columns,
+        _ => &[],
+    }
+}
+
+fn parse_create_table_statement<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+) -> Result<Statement, ParseError> {
+    match stream.next() {
+        Some((_, Keyword(Keyword::Table))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "TABLE after CREATE")),
+        None => return Err(ParseError::eof("TABLE after CREATE")),
+    }
+    let if_not_exists = parse_if_not_exists(stream)?;
+    let name = match stream.next() {
+        Some((_, Identifier(name))) => name,
+        Some((pos, token)) => return Err(ParseError::expected_name(token, pos, "table name after CREATE TABLE")),
+        None => return Err(ParseError::eof("table name after CREATE TABLE")),
+    };
+    let open_pos = match stream.next() {
+        Some((_, Keyword(Keyword::As))) => {
+            let query = match stream.next() {
+                Some((_, Keyword(Keyword::Select))) => parse_select_statement(stream, options)?,
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "SELECT after AS")),
+                None => return Err(ParseError::eof("SELECT after AS")),
+            };
+            return Ok(Statement::CreateTableAs {
+                table_name: name.to_string(),
+                if_not_exists,
+                query: Box::new(query),
+            });
+        }
+        Some((pos, LParen)) => pos,
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "opening parenthesis or AS after table name")),
+        None => return Err(ParseError::eof("opening parenthesis or AS after table name")),
+    };
+    let malformed = |problem, pos| ParseError::MalformedColumnList {
+        table: name.to_string(),
+        problem,
+        pos,
+    };
+    let mut column_list = Vec::new();
+    loop {
+        match stream.next() {
+            Some((token_index, Identifier(col_name))) => {
+                let column = parse_table_column(col_name.clone(), token_index, stream, options)?;
+                column_list.push(column);
+            }
+            Some((index, RParen)) if column_list.is_empty() => return Err(malformed(ColumnListProblem::Empty, index)),
+            // Only reachable right after a comma.
+            Some((index, RParen)) => return Err(malformed(ColumnListProblem::TrailingComma, index)),
+            Some((_, Eof)) | None => return Err(malformed(ColumnListProblem::Unclosed, open_pos)),
+            Some((index, token)) => return Err(ParseError::expected_name(token, index, "column name")),
+        }
+        match stream.next() {
+            Some((_, Comma)) => {}
+            Some((_, RParen)) => break,
+            Some((_, Eof)) | None => return Err(malformed(ColumnListProblem::Unclosed, open_pos)),
+            Some((index, token)) => {
+                return Err(ParseError::unexpected(token, index, "comma or closing parenthesis after column definition"))
+            }
+        }
+    }
+    let comment = parse_inline_comment(stream)?;
+    validate_check_constraints(name, &column_list)?;
+    Ok(Statement::CreateTable {
+        table_name: name.to_string(),
+        if_not_exists,
+        column_list,
+        comment,
+    })
+}
+
+// Maps the type starting at `token` to a column type, reading the rest of a
+// multi-token type (`CHARACTER VARYING`, `CHAR(n)`) from the stream. None means
+// `token` does not start a type.
+fn data_type<'a>(
+    token: &Token,
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+) -> Result<Option<DBType>, ParseError> {
+    let column_type = match token {
+        Int => DBType::Int,
+        Varchar(len) => DBType::Varchar(len.unwrap_or(options.default_varchar_len)),
+        Bool => DBType::Bool,
+        Keyword(Keyword::Character) if matches!(stream.peek(), Some((_, Keyword(Keyword::Varying)))) => {
+            stream.next();
+            DBType::Varchar(parse_type_length(stream, "CHARACTER VARYING")?.unwrap_or(options.default_varchar_len))
+        }
+        Keyword(k @ (Keyword::Char | Keyword::Character)) => {
+            DBType::Char(parse_type_length(stream, k.as_str())?.unwrap_or(1))
+        }
+        Keyword(Keyword::Uuid) => DBType::Uuid,
+        Keyword(Keyword::Json) => DBType::Json,
+        Keyword(Keyword::Jsonb) => DBType::Jsonb,
+        Keyword(Keyword::Xml) => DBType::Xml,
+        Keyword(Keyword::Bytea) => DBType::Bytea,
+        Keyword(Keyword::Serial) => DBType::Serial,
+        Keyword(Keyword::Bigserial) => DBType::BigSerial,
+        Keyword(Keyword::Smallserial) => DBType::SmallSerial,
+        _ => return Ok(None),
+    };
+    Ok(Some(column_type))
+}
+
+// Parses the optional `(n)` length after a character type.
+fn parse_type_length<'a>(stream: &mut TokenStream<'a>, type_name: &str) -> Result<Option<u64>, ParseError> {
+    if !matches!(stream.peek(), Some((_, LParen))) {
+        return Ok(None);
+    }
+    stream.next();
+    let len = match stream.next() {
+        Some((_, Number(n))) if *n > 0 => *n as u64,
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, &format!("positive length for {}", type_name))),
+        None => return Err(ParseError::eof(&format!("length for {}", type_name))),
+    };
+    match stream.next() {
+        Some((_, RParen)) => Ok(Some(len)),
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, &format!("')' after length for {}", type_name))),
+        None => Err(ParseError::eof(&format!("')' after length for {}", type_name))),
+    }
+}
+
+// Parses the parenthesized expression after CHECK; `owner` names what the
+// constraint belongs to in error messages.
+fn parse_check_expression<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions, owner: &str) -> Result<Expression, ParseError> {
+    let expected = format!("opening parenthesis after CHECK for {}", owner);
+    match stream.next() {
+        Some((_, LParen)) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, &expected)),
+        None => return Err(ParseError::eof(&expected)),
+    }
+    let expr = parse_clause_expression(stream, options, &format!("CHECK expression for {}", owner))?;
+    let expected = format!("closing parenthesis after CHECK expression for {}", owner);
+    match stream.next() {
+        Some((_, RParen)) => Ok(expr),
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, &expected)),
+        None => Err(ParseError::eof(&expected)),
+    }
+}
+
+// A CHECK constraint may refer to any column of the table being created, but
+// only to those. A `t.col` reference must be qualified by the table's own name,
+// either in full or by its last part (`t` for `public.t`).
+fn validate_check_constraints(table_name: &str, columns: &[TableColumn]) -> Result<(), ParseError> {
+    let own_qualifier = |qualifier: &str| {
+        qualifier == table_name || table_name.strip_suffix(qualifier).is_some_and(|schema| schema.ends_with('.'))
+    };
+    for column in columns {
+        for constraint in &column.constraints {
+            if let Constraint::Check(expr) = constraint {
+                let mut problem = None;
+                for_each_identifier(expr, &mut |name| {
+                    if problem.is_some() {
+                        return;
+                    }
+                    let (qualifier, name) = match name.rsplit_once('.') {
+                        Some((qualifier, name)) => (Some(qualifier), name),
+                        None => (None, name),
+                    };
+                    match qualifier {
+                        Some(qualifier) if !own_qualifier(qualifier) => {
+                            problem = Some(format!("other table '{}'", qualifier));
+                        }
+                        _ if !columns.iter().any(|c| c.column_name == name) => {
+                            problem = Some(format!("unknown column '{}'", name));
+                        }
+                        _ => {}
+                    }
+                });
+                if let Some(problem) = problem {
+                    return Err(ParseError::InvalidExpression {
+                        reason: format!(
+                            "CHECK constraint on column '{}' at token {} references {}",
+                            column.column_name, column.token_index, problem
+                        ),
+                        pos: column.token_index,
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_table_column<'a>(
+    column_name: Symbol,
+    token_index: usize,
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+) -> Result<TableColumn, ParseError> {
+    let expected = format!("data type for column '{}'", column_name);
+    let column_type = match stream.next() {
+        Some((pos, token)) => match data_type(token, stream, options)? {
+            Some(column_type) => column_type,
+            None => return Err(ParseError::unexpected(token, pos, &expected)),
+        },
+        None => return Err(ParseError::eof(&expected)),
+    };
+
+    let mut constraints = Vec::new();
+    let mut comment = None;
+    while let Some((pos, token)) = stream.peek() {
+        match token {
+            PrimaryKey => {
+                constraints.push(Constraint::PrimaryKey);
+                stream.next();
+                if let Some((_, Keyword(k))) = stream.peek() {
+                    if *k == Keyword::Key {
+                        stream.next();
+                    }
+                }
+            }
+            Keyword(Keyword::Not) => {
+                stream.next();
+                let expected = format!("NULL after NOT for column '{}'", column_name);
+                match stream.next() {
+                    Some((_, Keyword(Keyword::Null))) => constraints.push(Constraint::NotNull),
+                    Some((pos, token)) => return Err(ParseError::unexpected(token, pos, &expected)),
+                    None => return Err(ParseError::eof(&expected)),
+                }
+            }
+            Check => {
+                stream.next();
+                let owner = format!("column '{}' at token {}", column_name, token_index);
+                constraints.push(Constraint::Check(parse_check_expression(stream, options, &owner)?));
+            }
+            // MySQL dumps place COMMENT among the constraints, in any order.
+            Identifier(word) if word.eq_ignore_ascii_case("COMMENT") => {
+                if comment.is_some() {
+                    let expected = format!("a single COMMENT for column '{}'", column_name);
+                    return Err(ParseError::unexpected(token, pos, &expected));
+                }
+                comment = parse_inline_comment(stream)?;
+            }
+            Comma | RParen => break,
+            _ => break, // Stop if it's not a constraint keyword
+        }
+    }
+
+    Ok(TableColumn {
+        column_name,
+        column_type,
+        constraints,
+        comment,
+        token_index,
+    })
+}
+
+// Parses an optional `COMMENT 'text'` on a column or table definition. COMMENT
+// is not reserved, so it is matched by name.
+fn parse_inline_comment<'a>(stream: &mut TokenStream<'a>) -> Result<Option<String>, ParseError> {
+    match stream.peek() {
+        Some((_, Identifier(word))) if word.eq_ignore_ascii_case("COMMENT") => {
+            stream.next();
+        }
+        _ => return Ok(None),
+    }
+    match stream.next() {
+        Some((_, StringLiteral(text))) => Ok(Some(text.clone())),
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, "comment text after COMMENT")),
+        None => Err(ParseError::eof("comment text after COMMENT")),
+    }
+}
+
+fn parse_create_index_statement<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<Statement, ParseError> {
+    let mut unique = false;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Unique {
+            stream.next();
+            unique = true;
+        }
+    }
+    match stream.next() {
+        Some((_, Keyword(Keyword::Index))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "INDEX after CREATE UNIQUE")),
+        None => return Err(ParseError::eof("INDEX after CREATE UNIQUE")),
+    }
+
+    let mut concurrently = false;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Concurrently {
+            stream.next();
+            concurrently = true;
+        }
+    }
+    let if_not_exists = parse_if_not_exists(stream)?;
+
+    let name = match stream.next() {
+        Some((_, Identifier(name))) => name.to_string(),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "index name")),
+        None => return Err(ParseError::eof("index name")),
+    };
+    match stream.next() {
+        Some((_, Keyword(Keyword::On))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "ON after index name")),
+        None => return Err(ParseError::eof("ON after index name")),
+    }
+    let table = match stream.next() {
+        Some((_, Identifier(name))) => name.to_string(),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "table name after ON")),
+        None => return Err(ParseError::eof("table name after ON")),
+    };
+
+    expect_tokens(stream, &[LParen], "opening parenthesis after table name")?;
+    let mut columns = Vec::new();
+    loop {
+        let expr = parse_clause_expression(stream, options, "index expression")?;
+        let direction = parse_order_direction(stream);
+        columns.push(IndexColumn { expr, direction });
+        match stream.next() {
+            Some((_, Comma)) => continue,
+            Some((_, RParen)) => break,
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "comma or closing parenthesis after index column")),
+            None => return Err(ParseError::eof("comma or closing parenthesis after index column")),
+        }
+    }
+
+    let mut r#where = None;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Where {
+            stream.next(); // Consume WHERE
+            r#where = Some(parse_clause_expression(stream, options, "WHERE clause")?);
+        }
+    }
+
+    Ok(Statement::CreateIndex {
+        name,
+        table,
+        columns,
+        unique,
+        concurrently,
+        if_not_exists,
+        r#where,
+    })
+}
+
+fn parse_create_view_statement<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+) -> Result<Statement, ParseError> {
+    let mut or_replace = false;
+    if let Some((_, Operator(op))) = stream.peek() {
+        if op == "OR" {
+            stream.next();
+            match stream.next() {
+                Some((_, Keyword(Keyword::Replace))) => or_replace = true,
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "REPLACE after CREATE OR")),
+                None => return Err(ParseError::eof("REPLACE after CREATE OR")),
+            }
+        }
+    }
+    match stream.next() {
+        Some((_, Keyword(Keyword::View))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "VIEW after CREATE OR REPLACE")),
+        None => return Err(ParseError::eof("VIEW after CREATE OR REPLACE")),
+    }
+
+    let name = match stream.next() {
+        Some((_, Identifier(name))) => name.to_string(),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "view name")),
+        None => return Err(ParseError::eof("view name")),
+    };
+
+    let mut columns = None;
+    if let Some((_, LParen)) = stream.peek() {
+        stream.next();
+        let mut names = Vec::new();
+        loop {
+            match stream.next() {
+                Some((_, Identifier(column))) => names.push(column.to_string()),
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "column name in view column list")),
+                None => return Err(ParseError::eof("column name in view column list")),
+            }
+            match stream.next() {
+                Some((_, Comma)) => continue,
+                Some((_, RParen)) => break,
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "comma or closing parenthesis in view column list")),
+                None => return Err(ParseError::eof("comma or closing parenthesis in view column list")),
+            }
+        }
+        columns = Some(names);
+    }
+
+    match stream.next() {
+        Some((_, Keyword(Keyword::As))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "AS after view name")),
+        None => return Err(ParseError::eof("AS after view name")),
+    }
+    let query = match stream.next() {
+        Some((_, Keyword(Keyword::Select))) => parse_select_statement(stream, options)?,
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "SELECT after AS")),
+        None => return Err(ParseError::eof("SELECT after AS")),
+    };
+
+    let mut with_check_option = false;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::With {
+            stream.next();
+            expect_tokens(stream, &[Check, Keyword(Keyword::Option)], "CHECK OPTION after WITH")?;
+            with_check_option = true;
+        }
+    }
+
+    Ok(Statement::CreateView {
+        name,
+        or_replace,
+        columns,
+        query: Box::new(query),
+        with_check_option,
+    })
+}
+
+fn parse_create_database_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    stream.next(); // Consume DATABASE
+    let if_not_exists = parse_if_not_exists(stream)?;
+    let name = match stream.next() {
+        Some((_, Identifier(name))) => name.to_string(),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "database name")),
+        None => return Err(ParseError::eof("database name")),
+    };
+    Ok(Statement::CreateDatabase { name, if_not_exists })
+}
+
+// Parses the rest of a DROP DATABASE statement; DROP DATABASE has already been consumed.
+fn parse_drop_database_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    let if_exists = parse_if_exists(stream)?;
+    let name = match stream.next() {
+        Some((_, Identifier(name))) => name.to_string(),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "database name")),
+        None => return Err(ParseError::eof("database name")),
+    };
+    let cascade = parse_drop_behavior(stream);
+    match stream.peek() {
+        Some((_, Eof)) | Some((_, Semicolon)) | None => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "end of DROP DATABASE statement")),
+    }
+    Ok(Statement::DropDatabase {
+        name,
+        if_exists,
+        cascade,
+    })
+}
+
+// Parses the rest of an ALTER TABLE statement; ALTER has already been consumed.
+fn parse_alter_table_statement<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+) -> Result<Statement, ParseError> {
+    match stream.next() {
+        Some((_, Keyword(Keyword::Table))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "TABLE after ALTER")),
+        None => return Err(ParseError::eof("TABLE after ALTER")),
+    }
+    let name = parse_object_name(stream, "table")?;
+    let action = match stream.next() {
+        Some((_, Keyword(Keyword::Rename))) => parse_rename_action(stream)?,
+        Some((_, Keyword(Keyword::Add))) => AlterAction::AddConstraint(parse_table_constraint(stream, options)?),
+        Some((_, Keyword(Keyword::Alter))) => {
+            skip_column_noise_word(stream);
+            let name = parse_object_name(stream, "column")?;
+            let action = parse_column_alteration(stream, options)?;
+            AlterAction::AlterColumn { name, action }
+        }
+        Some((_, Keyword(Keyword::Drop))) => match stream.next() {
+            Some((_, Keyword(Keyword::Constraint))) => {
+                let name = parse_object_name(stream, "constraint")?;
+                let cascade = parse_drop_behavior(stream);
+                AlterAction::DropConstraint { name, cascade }
+            }
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "CONSTRAINT after ALTER TABLE ... DROP")),
+            None => return Err(ParseError::eof("CONSTRAINT after ALTER TABLE ... DROP")),
+        },
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "RENAME, ADD, ALTER or DROP after ALTER TABLE name")),
+        None => return Err(ParseError::eof("an action after ALTER TABLE name")),
+    };
+    Ok(Statement::AlterTable { name, action })
+}
+
+// Parses what ALTER COLUMN changes: `[SET DATA] TYPE type`, `SET DEFAULT expr`,
+// `DROP DEFAULT`, or `{SET | DROP} NOT NULL`. DATA and TYPE are matched by name.
+fn parse_column_alteration<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+) -> Result<ColumnAlteration, ParseError> {
+    let set = match stream.next() {
+        Some((_, Keyword(Keyword::Set))) => true,
+        Some((_, Keyword(Keyword::Drop))) => false,
+        Some((_, Identifier(word))) if word.eq_ignore_ascii_case("TYPE") => return parse_alteration_type(stream, options),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "TYPE, SET or DROP after ALTER COLUMN name")),
+        None => return Err(ParseError::eof("TYPE, SET or DROP after ALTER COLUMN name")),
+    };
+    let expected = if set {
+        "DATA TYPE, DEFAULT or NOT NULL after SET"
+    } else {
+        "DEFAULT or NOT NULL after DROP"
+    };
+    let (pos, first) = stream.next().ok_or_else(|| ParseError::eof(expected))?;
+    match (first, stream.peek()) {
+        (Identifier(data), Some((_, Identifier(word))))
+            if set && data.eq_ignore_ascii_case("DATA") && word.eq_ignore_ascii_case("TYPE") =>
+        {
+            stream.next();
+            parse_alteration_type(stream, options)
+        }
+        (Keyword(Keyword::Default), _) if set => {
+            Ok(ColumnAlteration::SetDefault(parse_clause_expression(stream, options, "DEFAULT expression")?))
+        }
+        (Keyword(Keyword::Default), _) => Ok(ColumnAlteration::DropDefault),
+        (Keyword(Keyword::Not), Some((_, Keyword(Keyword::Null)))) => {
+            stream.next();
+            Ok(if set {
+                ColumnAlteration::SetNotNull
+            } else {
+                ColumnAlteration::DropNotNull
+            })
+        }
+        _ => Err(ParseError::unexpected(first, pos, expected)),
+    }
+}
+
+// Parses the type after `TYPE` in ALTER COLUMN.
+fn parse_alteration_type<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<ColumnAlteration, ParseError> {
+    match stream.next() {
+        Some((pos, token)) => match data_type(token, stream, options)? {
+            Some(column_type) => Ok(ColumnAlteration::SetType(column_type)),
+            None => Err(ParseError::unexpected(token, pos, "a type after TYPE")),
+        },
+        None => Err(ParseError::eof("a type after TYPE")),
+    }
+}
+
+// Parses `[CONSTRAINT name] PRIMARY KEY (col, ...) | CHECK (expr)` after ADD.
+fn parse_table_constraint<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<TableConstraint, ParseError> {
+    let name = match stream.peek() {
+        Some((_, Keyword(Keyword::Constraint))) => {
+            stream.next();
+            Some(parse_object_name(stream, "constraint")?)
+        }
+        _ => None,
+    };
+    let owner = match &name {
+        Some(name) => format!("constraint '{}'", name),
+        None => "table constraint".to_string(),
+    };
+    match stream.next() {
+        Some((_, PrimaryKey)) => {
+            expect_tokens(stream, &[Keyword(Keyword::Key), LParen], &format!("KEY (columns) after PRIMARY for {}", owner))?;
+            let mut columns = Vec::new();
+            loop {
+                columns.push(parse_object_name(stream, "column")?);
+                match stream.next() {
+                    Some((_, Comma)) => {}
+                    Some((_, RParen)) => break,
+                    Some((pos, token)) => return Err(ParseError::unexpected(token, pos, &format!("',' or ')' in PRIMARY KEY columns for {}", owner))),
+                    None => return Err(ParseError::eof(&format!("',' or ')' in PRIMARY KEY columns for {}", owner))),
+                }
+            }
+            Ok(TableConstraint {
+                name,
+                constraint: Constraint::PrimaryKey,
+                columns,
+            })
+        }
+        Some((_, Check)) => Ok(TableConstraint {
+            name,
+            constraint: Constraint::Check(parse_check_expression(stream, options, &owner)?),
+            columns: Vec::new(),
+        }),
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, &format!("PRIMARY KEY or CHECK for {}", owner))),
+        None => Err(ParseError::eof(&format!("PRIMARY KEY or CHECK for {}", owner))),
+    }
+}
+
+// Consumes the optional COLUMN in `ALTER [COLUMN] name` and `RENAME [COLUMN] name`.
+// COLUMN is not reserved, so it is only the noise word when a name follows it.
+fn skip_column_noise_word<'a>(stream: &mut TokenStream<'a>) {
+    if let (Some((_, Identifier(word))), Some(Identifier(_))) = (stream.peek(), stream.remaining().get(1)) {
+        if word.eq_ignore_ascii_case("COLUMN") {
+            stream.next();
+        }
+    }
+}
+
+// Parses `TO new | [COLUMN] old TO new` after ALTER TABLE ... RENAME.
+fn parse_rename_action<'a>(stream: &mut TokenStream<'a>) -> Result<AlterAction, ParseError> {
+    if let Some((_, Keyword(Keyword::To))) = stream.peek() {
+        stream.next();
+        return Ok(AlterAction::RenameTable(parse_object_name(stream, "new table")?));
+    }
+    skip_column_noise_word(stream);
+    let from = parse_object_name(stream, "column")?;
+    match stream.next() {
+        Some((_, Keyword(Keyword::To))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, &format!("TO after RENAME COLUMN {}", from))),
+        None => return Err(ParseError::eof(&format!("TO after RENAME COLUMN {}", from))),
+    }
+    let to = parse_object_name(stream, "new column")?;
+    Ok(AlterAction::RenameColumn { from, to })
+}
+
+// Parses the rest of a CREATE SCHEMA statement; CREATE has already been consumed.
+fn parse_create_schema_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    stream.next(); // Consume SCHEMA
+    let if_not_exists = parse_if_not_exists(stream)?;
+    let (name, authorization) = match stream.peek() {
+        Some((_, Keyword(Keyword::Authorization))) => {
+            stream.next();
+            let owner = parse_object_name(stream, "role")?;
+            (owner.clone(), Some(owner))
+        }
+        _ => {
+            let name = parse_object_name(stream, "schema")?;
+            let authorization = match stream.peek() {
+                Some((_, Keyword(Keyword::Authorization))) => {
+                    stream.next();
+                    Some(parse_object_name(stream, "role")?)
+                }
+                _ => None,
+            };
+            (name, authorization)
+        }
+    };
+    Ok(Statement::CreateSchema {
+        name,
+        if_not_exists,
+        authorization,
+    })
+}
+
+// Parses the rest of a CREATE TYPE statement; CREATE has already been consumed.
+fn parse_create_type_statement<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<Statement, ParseError> {
+    stream.next(); // Consume TYPE
+    let name = parse_object_name(stream, "type")?;
+    match stream.next() {
+        Some((_, Keyword(Keyword::As))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "AS after CREATE TYPE name")),
+        None => return Err(ParseError::eof("AS after CREATE TYPE name")),
+    }
+    let definition = match stream.next() {
+        Some((_, Keyword(Keyword::Enum))) => TypeDefinition::Enum(parse_enum_labels(stream)?),
+        Some((_, Identifier(word))) if word.eq_ignore_ascii_case("RANGE") => parse_range_type(stream)?,
+        Some((_, LParen)) => {
+            let mut fields = Vec::new();
+            loop {
+                match stream.next() {
+                    Some((token_index, Identifier(field))) => {
+                        fields.push(parse_table_column(field.clone(), token_index, stream, options)?);
+                    }
+                    Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "field name in composite type")),
+                    None => return Err(ParseError::eof("field name in composite type")),
+                }
+                match stream.next() {
+                    Some((_, Comma)) => {}
+                    Some((_, RParen)) => break,
+                    Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "',' or ')' after composite type field")),
+                    None => return Err(ParseError::eof("',' or ')' after composite type field")),
+                }
+            }
+            TypeDefinition::Composite(fields)
+        }
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "ENUM, RANGE or '(' after CREATE TYPE ... AS")),
+        None => return Err(ParseError::eof("ENUM, RANGE or '(' after CREATE TYPE ... AS")),
+    };
+    Ok(Statement::CreateType { name, definition })
+}
+
+// Parses the `('label', ...)` list of an enum type; it may be empty.
+fn parse_enum_labels<'a>(stream: &mut TokenStream<'a>) -> Result<Vec<String>, ParseError> {
+    match stream.next() {
+        Some((_, LParen)) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "'(' after ENUM")),
+        None => return Err(ParseError::eof("'(' after ENUM")),
+    }
+    let mut labels = Vec::new();
+    if let Some((_, RParen)) = stream.peek() {
+        stream.next();
+        return Ok(labels);
+    }
+    loop {
+        match stream.next() {
+            Some((_, StringLiteral(label))) => labels.push(label.to_string()),
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "a string label in ENUM")),
+            None => return Err(ParseError::eof("a string label in ENUM")),
+        }
+        match stream.next() {
+            Some((_, Comma)) => {}
+            Some((_, RParen)) => return Ok(labels),
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "',' or ')' in ENUM labels")),
+            None => return Err(ParseError::eof("',' or ')' in ENUM labels")),
+        }
+    }
+}
+
+// Parses `(SUBTYPE = type)` after RANGE.
+fn parse_range_type<'a>(stream: &mut TokenStream<'a>) -> Result<TypeDefinition, ParseError> {
+    expect_tokens(
+        stream,
+        &[LParen, Keyword(Keyword::Subtype), Operator("=".to_string())],
+        "(SUBTYPE = type) after RANGE",
+    )?;
+    let subtype = match stream.next() {
+        Some((_, Identifier(name))) => name.to_string(),
+        Some((_, token @ (Int | Varchar(_) | Bool))) => token.to_string(),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "a type name after SUBTYPE =")),
+        None => return Err(ParseError::eof("a type name after SUBTYPE =")),
+    };
+    match stream.next() {
+        Some((_, RParen)) => Ok(TypeDefinition::Range { subtype }),
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, "')' after RANGE subtype")),
+        None => Err(ParseError::eof("')' after RANGE subtype")),
+    }
+}
+
+// Parses the rest of a DROP SCHEMA statement; DROP SCHEMA has already been consumed.
+fn parse_drop_schema_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    let if_exists = parse_if_exists(stream)?;
+    let name = parse_object_name(stream, "schema")?;
+    let cascade = parse_drop_behavior(stream);
+    match stream.peek() {
+        Some((_, Eof)) | Some((_, Semicolon)) | None => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "end of DROP SCHEMA statement")),
+    }
+    Ok(Statement::DropSchema {
+        name,
+        if_exists,
+        cascade,
+    })
+}
+
+// Parses the rest of a COMMENT ON statement; COMMENT has already been consumed.
+fn parse_comment_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    match stream.next() {
+        Some((_, Keyword(Keyword::On))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "ON after COMMENT")),
+        None => return Err(ParseError::eof("ON after COMMENT")),
+    }
+    let object = match stream.next() {
+        Some((_, Keyword(Keyword::Table))) => CommentObject::Table(parse_object_name(stream, "table")?),
+        Some((_, Keyword(Keyword::Database))) => CommentObject::Database(parse_object_name(stream, "database")?),
+        Some((_, Keyword(Keyword::Schema))) => CommentObject::Schema(parse_object_name(stream, "schema")?),
+        Some((_, Keyword(Keyword::Index))) => CommentObject::Index(parse_object_name(stream, "index")?),
+        Some((_, Keyword(Keyword::View))) => CommentObject::View(parse_object_name(stream, "view")?),
+        Some((_, Identifier(word))) if word.eq_ignore_ascii_case("COLUMN") => {
+            let pos = stream.index();
+            let name = parse_object_name(stream, "column")?;
+            match name.rsplit_once('.') {
+                Some((table, column)) => CommentObject::Column(table.to_string(), column.to_string()),
+                None => {
+                    return Err(ParseError::unexpected(&stream.tokens[pos], pos, "table.column after COMMENT ON COLUMN"))
+                }
+            }
+        }
+        Some((pos, token)) => {
+            return Err(ParseError::unexpected(
+                token,
+                pos,
+                "TABLE, COLUMN, DATABASE, SCHEMA, INDEX or VIEW after COMMENT ON",
+            ))
+        }
+        None => return Err(ParseError::eof("TABLE, COLUMN, DATABASE, SCHEMA, INDEX or VIEW after COMMENT ON")),
+    };
+    match stream.next() {
+        Some((_, Keyword(Keyword::Is))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "IS after COMMENT ON object")),
+        None => return Err(ParseError::eof("IS after COMMENT ON object")),
+    }
+    let value = match stream.next() {
+        Some((_, StringLiteral(text))) => Some(text.to_string()),
+        Some((_, Keyword(Keyword::Null))) => None,
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "a string or NULL after IS")),
+        None => return Err(ParseError::eof("a string or NULL after IS")),
+    };
+    Ok(Statement::Comment { object, value })
+}
+
+// Parses the rest of a SET statement; SET has already been consumed.
+fn parse_set_statement<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<Statement, ParseError> {
+    // SESSION, GLOBAL and LOCAL are not reserved: one of them is the scope only
+    // when the variable name follows it, so `SET local = 1` sets `local`.
+    let mut scope = match (stream.peek(), stream.remaining().get(1)) {
+        (Some((_, Identifier(word))), Some(Identifier(_))) => match word.to_ascii_uppercase().as_str() {
+            "SESSION" => Some(SetScope::Session),
+            "GLOBAL" => Some(SetScope::Global),
+            "LOCAL" => Some(SetScope::Local),
+            _ => None,
+        },
+        _ => None,
+    };
+    if scope.is_some() {
+        stream.next();
+    }
+
+    let mut variable = match stream.next() {
+        Some((_, Identifier(name))) => name.to_string(),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "variable name after SET")),
+        None => return Err(ParseError::eof("variable name after SET")),
+    };
+    // MySQL spells the scope as a prefix of the variable: `@@global.sort_buffer_size`.
+    if scope.is_none() {
+        if let Some((prefix, name)) = variable.split_once('.') {
+            let prefixed_scope = match prefix.to_ascii_lowercase().as_str() {
+                "@@session" => Some(SetScope::Session),
+                "@@global" => Some(SetScope::Global),
+                "@@local" => Some(SetScope::Local),
+                _ => None,
+            };
+            if prefixed_scope.is_some() {
+                scope = prefixed_scope;
+                variable = name.to_string();
+            }
+        }
+    }
+
+    match stream.peek() {
+        Some((_, Operator(op))) if op == "=" => {
+            stream.next();
+        }
+        Some((_, Keyword(Keyword::To))) => {
+            stream.next();
+        }
+        // `SET NAMES 'utf8'` takes its value without an assignment operator.
+        _ if variable.eq_ignore_ascii_case("NAMES") => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, &format!("'=' or TO after SET {}", variable))),
+        None => return Err(ParseError::eof(&format!("'=' or TO after SET {}", variable))),
+    }
+
+    let mut values = Vec::new();
+    loop {
+        values.push(parse_clause_expression(stream, options, "SET value")?);
+        if let Some((_, Comma)) = stream.peek() {
+            stream.next();
+        } else {
+            break;
+        }
+    }
+
+    Ok(Statement::Set {
+        variable,
+        values,
+        scope,
+    })
+}
+
+// Parses the rest of a CREATE SEQUENCE statement; CREATE has already been consumed.
+// The options may appear in any order, each at most once.
+fn parse_create_sequence_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    stream.next(); // Consume SEQUENCE
+    let if_not_exists = parse_if_not_exists(stream)?;
+    let name = parse_object_name(stream, "sequence")?;
+
+    let mut options = SequenceOptions::default();
+    let mut seen = Vec::new();
+    while let Some((pos, token @ Keyword(k))) = stream.peek() {
+        let option = *k;
+        if seen.contains(&option) {
+            return Err(ParseError::unexpected(token, pos, "each sequence option at most once"));
+        }
+        match option {
+            Keyword::Start => {
+                stream.next();
+                skip_keyword(stream, Keyword::With);
+                options.start = Some(parse_sequence_value(stream, "START")?);
+            }
+            Keyword::Increment => {
+                stream.next();
+                skip_keyword(stream, Keyword::By);
+                options.increment = Some(parse_sequence_value(stream, "INCREMENT")?);
+            }
+            Keyword::Minvalue => {
+                stream.next();
+                options.min_value = Some(parse_sequence_value(stream, "MINVALUE")?);
+            }
+            Keyword::Maxvalue => {
+                stream.next();
+                options.max_value = Some(parse_sequence_value(stream, "MAXVALUE")?);
+            }
+            Keyword::Cache => {
+                stream.next();
+                options.cache = Some(parse_sequence_value(stream, "CACHE")?);
+            }
+            Keyword::Cycle => {
+                stream.next();
+                options.cycle = Some(true);
+            }
+            // `NO MINVALUE | NO MAXVALUE | NO CYCLE`
+            Keyword::No => {
+                stream.next();
+                match stream.next() {
+                    Some((_, Keyword(Keyword::Minvalue | Keyword::Maxvalue))) => {}
+                    Some((_, Keyword(Keyword::Cycle))) => options.cycle = Some(false),
+                    Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "MINVALUE, MAXVALUE or CYCLE after NO")),
+                    None => return Err(ParseError::eof("MINVALUE, MAXVALUE or CYCLE after NO")),
+                }
+            }
+            Keyword::Owned => {
+                stream.next();
+                match stream.next() {
+                    Some((_, Keyword(Keyword::By))) => {}
+                    Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "BY after OWNED")),
+                    None => return Err(ParseError::eof("BY after OWNED")),
+                }
+                options.owned_by = match stream.next() {
+                    Some((_, Identifier(name))) if name.eq_ignore_ascii_case("NONE") => None,
+                    Some((_, Identifier(name))) => Some(name.to_string()),
+                    Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "column or NONE after OWNED BY")),
+                    None => return Err(ParseError::eof("column or NONE after OWNED BY")),
+                };
+            }
+            _ => break,
+        }
+        seen.push(option);
+    }
+
+    Ok(Statement::CreateSequence {
+        name,
+        if_not_exists,
+        options,
+    })
+}
+
+// Consumes `keyword` if it is next; used for optional noise words.
+fn skip_keyword<'a>(stream: &mut TokenStream<'a>, keyword: Keyword) {
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == keyword {
+            stream.next();
+        }
+    }
+}
+
+// Parses the possibly negative integer after a sequence option.
+fn parse_sequence_value<'a>(stream: &mut TokenStream<'a>, option: &str) -> Result<i64, ParseError> {
+    let negative = matches!(stream.peek(), Some((_, Operator(op))) if op == "-");
+    if negative {
+        stream.next();
+    }
+    match stream.next() {
+        Some((_, Number(n))) => Ok(if negative { -n } else { *n }),
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, &format!("a number after {}", option))),
+        None => Err(ParseError::eof(&format!("a number after {}", option))),
+    }
+}
+
+// Parses the rest of a MERGE statement; MERGE has already been consumed.
+fn parse_merge_statement<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<Statement, ParseError> {
+    match stream.next() {
+        Some((_, Keyword(Keyword::Into))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "INTO after MERGE")),
+        None => return Err(ParseError::eof("INTO after MERGE")),
+    }
+    let target = parse_object_name(stream, "target table")?;
+    let target_alias = parse_table_alias(stream)?;
+    match stream.next() {
+        Some((_, Keyword(Keyword::Using))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "USING after MERGE target")),
+        None => return Err(ParseError::eof("USING after MERGE target")),
+    }
+    let source = parse_table_ref(stream, options)?;
+    match stream.next() {
+        Some((_, Keyword(Keyword::On))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "ON after MERGE source")),
+        None => return Err(ParseError::eof("ON after MERGE source")),
+    }
+    let on = parse_clause_expression(stream, options, "MERGE condition")?;
+
+    let mut clauses = Vec::new();
+    while let Some((_, Keyword(k))) = stream.peek() {
+        if *k != Keyword::When {
+            break;
+        }
+        stream.next();
+        clauses.push(parse_merge_clause(stream, options)?);
+    }
+    if clauses.is_empty() {
+        return Err(match stream.peek() {
+            Some((pos, token)) => ParseError::unexpected(token, pos, "WHEN clause in MERGE"),
+            None => ParseError::eof("WHEN clause in MERGE"),
+        });
+    }
+
+    Ok(Statement::Merge {
+        target,
+        target_alias,
+        source,
+        on,
+        clauses,
+    })
+}
+
+// Parses one `[NOT] MATCHED [BY SOURCE | BY TARGET] [AND condition] THEN action`
+// arm; WHEN has already been consumed.
+fn parse_merge_clause<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<MergeClause, ParseError> {
+    let not_matched = matches!(stream.peek(), Some((_, Keyword(Keyword::Not))));
+    if not_matched {
+        stream.next();
+    }
+    match stream.next() {
+        Some((_, Keyword(Keyword::Matched))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "MATCHED or NOT MATCHED after WHEN")),
+        None => return Err(ParseError::eof("MATCHED or NOT MATCHED after WHEN")),
+    }
+    // SOURCE and TARGET are not keywords, so they stay usable as column names.
+    let mut by_source = false;
+    if not_matched {
+        if let Some((_, Keyword(k))) = stream.peek() {
+            if *k == Keyword::By {
+                stream.next();
+                by_source = match stream.next() {
+                    Some((_, Identifier(side))) if side.eq_ignore_ascii_case("SOURCE") => true,
+                    Some((_, Identifier(side))) if side.eq_ignore_ascii_case("TARGET") => false,
+                    Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "SOURCE or TARGET after NOT MATCHED BY")),
+                    None => return Err(ParseError::eof("SOURCE or TARGET after NOT MATCHED BY")),
+                };
+            }
+        }
+    }
+
+    let mut condition = None;
+    if let Some((_, Operator(op))) = stream.peek() {
+        if op == "AND" {
+            stream.next();
+            condition = Some(parse_clause_expression(stream, options, "WHEN condition")?);
+        }
+    }
+    match stream.next() {
+        Some((_, Keyword(Keyword::Then))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "THEN in MERGE WHEN clause")),
+        None => return Err(ParseError::eof("THEN in MERGE WHEN clause")),
+    }
+
+    let action = match stream.next() {
+        Some((pos, token @ Keyword(Keyword::Update | Keyword::Delete))) if not_matched && !by_source => {
+            return Err(ParseError::unexpected(token, pos, "INSERT after WHEN NOT MATCHED ... THEN"))
+        }
+        Some((_, Keyword(Keyword::Update))) => parse_merge_update(stream, options)?,
+        Some((_, Keyword(Keyword::Delete))) => MergeAction::Delete,
+        Some((pos, token @ Keyword(Keyword::Insert))) if !not_matched || by_source => {
+            return Err(ParseError::unexpected(token, pos, "UPDATE or DELETE after WHEN [NOT MATCHED BY SOURCE] ... THEN"))
+        }
+        Some((_, Keyword(Keyword::Insert))) => parse_merge_insert(stream, options)?,
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "UPDATE, DELETE or INSERT after THEN")),
+        None => return Err(ParseError::eof("UPDATE, DELETE or INSERT after THEN")),
+    };
+
+    Ok(if !not_matched {
+        MergeClause::Matched(condition, action)
+    } else if by_source {
+        MergeClause::NotMatchedBySource(condition, action)
+    } else {
+        MergeClause::NotMatchedByTarget(condition, action)
+    })
+}
+
+// Parses `SET col = expr [, ...]` after UPDATE in a MERGE action.
+fn parse_merge_update<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<MergeAction, ParseError> {
+    match stream.next() {
+        Some((_, Keyword(Keyword::Set))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "SET after UPDATE")),
+        None => return Err(ParseError::eof("SET after UPDATE")),
+    }
+    let mut assignments = Vec::new();
+    loop {
+        let column = parse_object_name(stream, "column")?;
+        match stream.next() {
+            Some((_, Operator(op))) if op == "=" => {}
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, &format!("'=' after column '{}' in UPDATE SET", column))),
+            None => return Err(ParseError::eof(&format!("'=' after column '{}' in UPDATE SET", column))),
+        }
+        assignments.push((column, parse_clause_expression(stream, options, "UPDATE SET value")?));
+        if let Some((_, Comma)) = stream.peek() {
+            stream.next();
+        } else {
+            break;
+        }
+    }
+    Ok(MergeAction::Update { assignments })
+}
+
+// Parses `[(col, ...)] VALUES (expr, ...)` after INSERT in a MERGE action.
+fn parse_merge_insert<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<MergeAction, ParseError> {
+    let mut columns = Vec::new();
+    if let Some((_, LParen)) = stream.peek() {
+        stream.next();
+        loop {
+            columns.push(parse_object_name(stream, "column")?);
+            match stream.next() {
+                Some((_, Comma)) => {}
+                Some((_, RParen)) => break,
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "',' or ')' in INSERT column list")),
+                None => return Err(ParseError::eof("',' or ')' in INSERT column list")),
+            }
+        }
+    }
+    match stream.next() {
+        Some((_, Keyword(Keyword::Values))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "VALUES (...) after INSERT")),
+        None => return Err(ParseError::eof("VALUES (...) after INSERT")),
+    }
+    let values_pos = stream.index();
+    let values = parse_value_tuple(stream, options)?;
+    if !columns.is_empty() && columns.len() != values.len() {
+        return Err(ParseError::InvalidExpression {
+            reason: format!("INSERT has {} columns but {} values", columns.len(), values.len()),
+            pos: values_pos,
+        });
+    }
+    Ok(MergeAction::Insert { columns, values })
+}
+
+// Parses the comma-separated `(expr, ...)` rows after VALUES, which must all
+// have as many values as the first.
+fn parse_values_rows<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<Vec<Vec<Expression>>, ParseError> {
+    let mut rows = vec![parse_value_tuple(stream, options)?];
+    while let Some((_, Comma)) = stream.peek() {
+        stream.next();
+        let row_pos = stream.index();
+        let row = parse_value_tuple(stream, options)?;
+        if row.len() != rows[0].len() {
+            return Err(ParseError::InvalidExpression {
+                reason: format!("VALUES row {} has {} values, expected {}", rows.len() + 1, row.len(), rows[0].len()),
+                pos: row_pos,
+            });
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+// Parses one parenthesized `(expr, ...)` row of a VALUES list.
+fn parse_value_tuple<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<Vec<Expression>, ParseError> {
+    match stream.next() {
+        Some((_, LParen)) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "'(' to start a VALUES row")),
+        None => return Err(ParseError::eof("'(' to start a VALUES row")),
+    }
+    let mut values = Vec::new();
+    loop {
+        values.push(parse_clause_expression(stream, options, "VALUES expression")?);
+        match stream.next() {
+            Some((_, Comma)) => {}
+            Some((_, RParen)) => break,
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "',' or ')' in VALUES row")),
+            None => return Err(ParseError::eof("',' or ')' in VALUES row")),
+        }
+    }
+    Ok(values)
+}
+
+// Parses the rest of a COPY statement; COPY has already been consumed.
+fn parse_copy_statement<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<Statement, ParseError> {
+    let table_or_query = match stream.next() {
+        Some((_, Identifier(name))) => {
+            let mut columns = None;
+            if let Some((_, LParen)) = stream.peek() {
+                stream.next();
+                let mut names = Vec::new();
+                loop {
+                    names.push(parse_object_name(stream, "column")?);
+                    match stream.next() {
+                        Some((_, Comma)) => {}
+                        Some((_, RParen)) => break,
+                        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "',' or ')' in COPY column list")),
+                        None => return Err(ParseError::eof("',' or ')' in COPY column list")),
+                    }
+                }
+                columns = Some(names);
+            }
+            CopySource::Table(name.to_string(), columns)
+        }
+        Some((_, LParen)) => {
+            let query = match stream.next() {
+                Some((_, Keyword(Keyword::Select))) => parse_select_statement(stream, options)?,
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "SELECT in COPY query")),
+                None => return Err(ParseError::eof("SELECT in COPY query")),
+            };
+            expect_tokens(stream, &[RParen], "closing parenthesis after COPY query")?;
+            CopySource::Query(Box::new(query))
+        }
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "table name or query after COPY")),
+        None => return Err(ParseError::eof("table name or query after COPY")),
+    };
+
+    let direction = match stream.next() {
+        Some((pos, token @ Keyword(Keyword::From))) if matches!(table_or_query, CopySource::Query(_)) => {
+            return Err(ParseError::unexpected(token, pos, "TO after COPY query"))
+        }
+        Some((_, Keyword(Keyword::From))) => CopyDirection::From(parse_copy_target(stream)?),
+        Some((_, Keyword(Keyword::To))) => CopyDirection::To(parse_copy_target(stream)?),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "FROM or TO in COPY")),
+        None => return Err(ParseError::eof("FROM or TO in COPY")),
+    };
+
+    let mut copy_options = Vec::new();
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::With {
+            stream.next();
+        }
+    }
+    if let Some((_, LParen)) = stream.peek() {
+        stream.next();
+        loop {
+            copy_options.push(parse_copy_option(stream)?);
+            match stream.next() {
+                Some((_, Comma)) => {}
+                Some((_, RParen)) => break,
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "comma or closing parenthesis in COPY options")),
+                None => return Err(ParseError::eof("comma or closing parenthesis in COPY options")),
+            }
+        }
+    }
+
+    Ok(Statement::Copy {
+        table_or_query,
+        direction,
+        options: copy_options,
+    })
+}
+
+fn parse_copy_target<'a>(stream: &mut TokenStream<'a>) -> Result<CopyTarget, ParseError> {
+    match stream.next() {
+        Some((_, Keyword(Keyword::Stdin))) => Ok(CopyTarget::Stdin),
+        Some((_, Keyword(Keyword::Stdout))) => Ok(CopyTarget::Stdout),
+        Some((_, StringLiteral(path))) => Ok(CopyTarget::Filename(path.to_string())),
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, "STDIN, STDOUT or a file name")),
+        None => Err(ParseError::eof("STDIN, STDOUT or a file name")),
+    }
+}
+
+fn parse_copy_option<'a>(stream: &mut TokenStream<'a>) -> Result<CopyOption, ParseError> {
+    let option = match stream.next() {
+        // FORMAT is not reserved, so it is matched by name.
+        Some((_, Identifier(option))) if option.eq_ignore_ascii_case("FORMAT") => match stream.next() {
+            Some((_, Identifier(name))) => CopyOption::Format(name.to_string()),
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "format name after FORMAT")),
+            None => return Err(ParseError::eof("format name after FORMAT")),
+        },
+        Some((_, Keyword(Keyword::Header))) => CopyOption::Header(parse_explain_flag(stream)),
+        Some((_, Keyword(Keyword::Delimiter))) => CopyOption::Delimiter(parse_copy_string(stream, "DELIMITER")?),
+        Some((_, Keyword(Keyword::Escape))) => CopyOption::Escape(parse_copy_string(stream, "ESCAPE")?),
+        Some((_, Keyword(Keyword::Null))) => CopyOption::Null(parse_copy_string(stream, "NULL")?),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "COPY option")),
+        None => return Err(ParseError::eof("COPY option")),
+    };
+    Ok(option)
+}
+
+fn parse_copy_string<'a>(stream: &mut TokenStream<'a>, option: &str) -> Result<String, ParseError> {
+    match stream.next() {
+        Some((_, StringLiteral(value))) => Ok(value.to_string()),
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, &format!("a string after {}", option))),
+        None => Err(ParseError::eof(&format!("a string after {}", option))),
+    }
+}
+
+// Parses the rest of a GRANT statement; GRANT has already been consumed.
+fn parse_grant_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    let privileges = parse_privileges(stream)?;
+    let on = parse_grant_object(stream)?;
+    match stream.next() {
+        Some((_, Keyword(Keyword::To))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "TO after GRANT object")),
+        None => return Err(ParseError::eof("TO after GRANT object")),
+    }
+    let to = parse_role_list(stream)?;
+
+    let mut with_grant_option = false;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::With {
+            stream.next();
+            expect_tokens(stream, &[Keyword(Keyword::Grant), Keyword(Keyword::Option)], "GRANT OPTION after WITH")?;
+            with_grant_option = true;
+        }
+    }
+
+    Ok(Statement::Grant {
+        privileges,
+        on,
+        to,
+        with_grant_option,
+    })
+}
+
+// Parses the rest of a REVOKE statement; REVOKE has already been consumed.
+fn parse_revoke_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    let privileges = parse_privileges(stream)?;
+    let on = parse_grant_object(stream)?;
+    match stream.next() {
+        Some((_, Keyword(Keyword::From))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "FROM after REVOKE object")),
+        None => return Err(ParseError::eof("FROM after REVOKE object")),
+    }
+    let from = parse_role_list(stream)?;
+    let cascade = parse_drop_behavior(stream);
+
+    Ok(Statement::Revoke {
+        privileges,
+        on,
+        from,
+        cascade,
+    })
+}
+
+// Parses `ALL [PRIVILEGES]` or a comma-separated privilege list, up to and
+// including the ON that follows it. ALL is matched by name.
+fn parse_privileges<'a>(stream: &mut TokenStream<'a>) -> Result<Vec<Privilege>, ParseError> {
+    let mut privileges = Vec::new();
+    loop {
+        let privilege = match stream.next() {
+            Some((_, Identifier(word))) if word.eq_ignore_ascii_case("ALL") => {
+                if let Some((_, Keyword(p))) = stream.peek() {
+                    if *p == Keyword::Privileges || *p == Keyword::Privilege {
+                        stream.next();
+                    }
+                }
+                Privilege::All
+            }
+            Some((_, Keyword(Keyword::Select))) => Privilege::Select,
+            Some((_, Keyword(Keyword::Insert))) => Privilege::Insert,
+            Some((_, Keyword(Keyword::Delete))) => Privilege::Delete,
+            Some((_, Keyword(Keyword::Update))) => match stream.peek() {
+                Some((_, LParen)) => {
+                    stream.next();
+                    let mut columns = Vec::new();
+                    loop {
+                        match stream.next() {
+                            Some((_, Identifier(name))) => columns.push(name.to_string()),
+                            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "column name in UPDATE privilege")),
+                            None => return Err(ParseError::eof("column name in UPDATE privilege")),
+                        }
+                        match stream.next() {
+                            Some((_, Comma)) => {}
+                            Some((_, RParen)) => break,
+                            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "',' or ')' in UPDATE privilege columns")),
+                            None => return Err(ParseError::eof("',' or ')' in UPDATE privilege columns")),
+                        }
+                    }
+                    Privilege::UpdateColumns(columns)
+                }
+                _ => Privilege::Update,
+            },
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "privilege")),
+            None => return Err(ParseError::eof("privilege")),
+        };
+        privileges.push(privilege);
+        match stream.next() {
+            Some((_, Comma)) => {}
+            Some((_, Keyword(Keyword::On))) => return Ok(privileges),
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "',' or ON after privilege")),
+            None => return Err(ParseError::eof("',' or ON after privilege")),
+        }
+    }
+}
+
+// Parses the object a privilege applies to; a bare name is a table, even `all`
+// unless TABLES follows it.
+fn parse_grant_object<'a>(stream: &mut TokenStream<'a>) -> Result<GrantObject, ParseError> {
+    match stream.next() {
+        Some((_, Keyword(Keyword::Table))) => Ok(GrantObject::Table(parse_object_name(stream, "table")?)),
+        Some((_, Keyword(Keyword::Schema))) => Ok(GrantObject::Schema(parse_object_name(stream, "schema")?)),
+        Some((_, Keyword(Keyword::Database))) => Ok(GrantObject::Database(parse_object_name(stream, "database")?)),
+        Some((_, Identifier(word)))
+            if word.eq_ignore_ascii_case("ALL") && matches!(stream.peek(), Some((_, Keyword(Keyword::Tables)))) =>
+        {
+            expect_tokens(
+                stream,
+                &[Keyword(Keyword::Tables), Keyword(Keyword::In), Keyword(Keyword::Schema)],
+                "TABLES IN SCHEMA after ON ALL",
+            )?;
+            Ok(GrantObject::AllTablesInSchema(parse_object_name(stream, "schema")?))
+        }
+        Some((_, Identifier(name))) => Ok(GrantObject::Table(name.to_string())),
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, "object after ON")),
+        None => Err(ParseError::eof("object after ON")),
+    }
+}
+
+// Consumes `tokens` in order; `expected` describes them all in the error for
+// the first one that is missing.
+fn expect_tokens<'a>(stream: &mut TokenStream<'a>, tokens: &[Token], expected: &str) -> Result<(), ParseError> {
+    for token in tokens {
+        match stream.next() {
+            Some((_, found)) if found == token => {}
+            Some((pos, found)) => return Err(ParseError::unexpected(found, pos, expected)),
+            None => return Err(ParseError::eof(expected)),
+        }
+    }
+    Ok(())
+}
+
+fn parse_object_name<'a>(stream: &mut TokenStream<'a>, kind: &str) -> Result<String, ParseError> {
+    match stream.next() {
+        Some((_, Identifier(name))) => Ok(name.to_string()),
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, &format!("{} name", kind))),
+        None => Err(ParseError::eof(&format!("{} name", kind))),
+    }
+}
+
+// Parses the comma-separated grantees after TO or FROM.
+fn parse_role_list<'a>(stream: &mut TokenStream<'a>) -> Result<Vec<String>, ParseError> {
+    let mut roles = Vec::new();
+    loop {
+        roles.push(parse_object_name(stream, "role")?);
+        if let Some((_, Comma)) = stream.peek() {
+            stream.next();
+        } else {
+            return Ok(roles);
+        }
+    }
+}
+
+// Parses the rest of a SHOW statement; SHOW has already been consumed.
+fn parse_show_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    let object = match stream.next() {
+        Some((_, Keyword(Keyword::Tables))) => ShowObject::Tables,
+        Some((_, Keyword(Keyword::Databases))) => ShowObject::Databases,
+        Some((_, Keyword(Keyword::Columns))) => ShowObject::Columns(parse_show_table_name(stream, "COLUMNS")?),
+        Some((_, Keyword(k @ (Keyword::Index | Keyword::Indexes)))) => {
+            ShowObject::Indexes(parse_show_table_name(stream, k.as_str())?)
+        }
+        Some((_, Keyword(Keyword::Create))) => match stream.next() {
+            Some((_, Keyword(Keyword::Table))) => match stream.next() {
+                Some((_, Identifier(name))) => ShowObject::CreateTable(name.to_string()),
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "table name after SHOW CREATE TABLE")),
+                None => return Err(ParseError::eof("table name after SHOW CREATE TABLE")),
+            },
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "TABLE after SHOW CREATE")),
+            None => return Err(ParseError::eof("TABLE after SHOW CREATE")),
+        },
+        Some((pos, token)) => {
+            return Err(ParseError::unexpected(
+                token,
+                pos,
+                "TABLES, DATABASES, COLUMNS, INDEX or CREATE TABLE after SHOW",
+            ))
+        }
+        None => return Err(ParseError::eof("TABLES, DATABASES, COLUMNS, INDEX or CREATE TABLE after SHOW")),
+    };
+    match stream.peek() {
+        Some((_, Eof)) | Some((_, Semicolon)) | None => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "end of SHOW statement")),
+    }
+    Ok(Statement::Show { object })
+}
+
+// Parses the `FROM table` (or MySQL's `IN table`) that follows SHOW COLUMNS and SHOW INDEX.
+fn parse_show_table_name<'a>(stream: &mut TokenStream<'a>, what: &str) -> Result<String, ParseError> {
+    match stream.next() {
+        Some((_, Keyword(Keyword::From | Keyword::In))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, &format!("FROM after SHOW {}", what))),
+        None => return Err(ParseError::eof(&format!("FROM after SHOW {}", what))),
+    }
+    match stream.next() {
+        Some((_, Identifier(name))) => Ok(name.to_string()),
+        Some((pos, token)) => Err(ParseError::unexpected(token, pos, &format!("table name after SHOW {} FROM", what))),
+        None => Err(ParseError::eof(&format!("table name after SHOW {} FROM", what))),
+    }
+}
+
+// Consumes an optional CASCADE or RESTRICT and reports whether CASCADE was given.
+fn parse_drop_behavior<'a>(stream: &mut TokenStream<'a>) -> bool {
+    match stream.peek() {
+        Some((_, Keyword(Keyword::Cascade))) => {
+            stream.next();
+            true
+        }
+        Some((_, Keyword(Keyword::Restrict))) => {
+            stream.next();
+            false
+        }
+        _ => false,
+    }
+}
+
+// Parses the rest of a DROP INDEX statement; DROP INDEX has already been consumed.
+fn parse_drop_index_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    let mut concurrently = false;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Concurrently {
+            stream.next();
+            concurrently = true;
+        }
+    }
+    let if_exists = parse_if_exists(stream)?;
+
+    let name = match stream.next() {
+        Some((_, Identifier(name))) => name.to_string(),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "index name")),
+        None => return Err(ParseError::eof("index name")),
+    };
+
+    let mut on_table = None;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::On {
+            stream.next();
+            match stream.next() {
+                Some((_, Identifier(table))) => on_table = Some(table.to_string()),
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "table name after ON")),
+                None => return Err(ParseError::eof("table name after ON")),
+            }
+        }
+    }
+
+    Ok(Statement::DropIndex {
+        name,
+        if_exists,
+        concurrently,
+        on_table,
+    })
+}
+
+// Parses `TRUNCATE [TABLE] t [, ...] [RESTART IDENTITY | CONTINUE IDENTITY]
+// [CASCADE | RESTRICT]`; TRUNCATE has already been consumed.
+fn parse_truncate_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Table {
+            stream.next();
+        }
+    }
+
+    let mut tables = Vec::new();
+    loop {
+        match stream.next() {
+            Some((_, Identifier(name))) => tables.push(name.to_string()),
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "table name in TRUNCATE")),
+            None => return Err(ParseError::eof("table name in TRUNCATE")),
+        }
+        if let Some((_, Comma)) = stream.peek() {
+            stream.next();
+        } else {
+            break;
+        }
+    }
+
+    let mut restart_identity = false;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Restart || *k == Keyword::Continue {
+            restart_identity = *k == Keyword::Restart;
+            stream.next();
+            match stream.next() {
+                Some((_, Keyword(Keyword::Identity))) => {}
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "IDENTITY after RESTART or CONTINUE")),
+                None => return Err(ParseError::eof("IDENTITY after RESTART or CONTINUE")),
+            }
+        }
+    }
+
+    let cascade = match stream.peek() {
+        Some((_, Keyword(Keyword::Cascade | Keyword::Restrict))) => Some(parse_drop_behavior(stream)),
+        _ => None,
+    };
+
+    Ok(Statement::Truncate {
+        tables,
+        restart_identity,
+        cascade,
+    })
+}
+
+// Parses `EXPLAIN [ANALYZE] [VERBOSE] statement` (MySQL and PostgreSQL) or the
+// PostgreSQL option list form `EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) statement`.
+fn parse_explain_statement<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+) -> Result<Statement, ParseError> {
+    let mut analyze = false;
+    let mut verbose = false;
+    let mut buffers = false;
+    let mut format = None;
+
+    if let Some((_, LParen)) = stream.peek() {
+        stream.next();
+        loop {
+            match stream.next() {
+                Some((_, Keyword(Keyword::Analyze))) => analyze = parse_explain_flag(stream),
+                Some((_, Keyword(Keyword::Verbose))) => verbose = parse_explain_flag(stream),
+                Some((_, Keyword(Keyword::Buffers))) => buffers = parse_explain_flag(stream),
+                // FORMAT is not reserved, so it is matched by name.
+                Some((_, Identifier(option))) if option.eq_ignore_ascii_case("FORMAT") => {
+                    format = Some(match stream.next() {
+                        // JSON and XML are type keywords; TEXT and YAML are plain words.
+                        Some((_, Keyword(Keyword::Json))) => ExplainFormat::Json,
+                        Some((_, Keyword(Keyword::Xml))) => ExplainFormat::Xml,
+                        Some((_, Identifier(name))) if name.eq_ignore_ascii_case("TEXT") => ExplainFormat::Text,
+                        Some((_, Identifier(name))) if name.eq_ignore_ascii_case("YAML") => ExplainFormat::Yaml,
+                        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "TEXT, JSON, XML or YAML after FORMAT")),
+                        None => return Err(ParseError::eof("TEXT, JSON, XML or YAML after FORMAT")),
+                    });
+                }
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "EXPLAIN option")),
+                None => return Err(ParseError::eof("EXPLAIN option")),
+            }
+            match stream.next() {
+                Some((_, Comma)) => continue,
+                Some((_, RParen)) => break,
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "comma or closing parenthesis in EXPLAIN options")),
+                None => return Err(ParseError::eof("comma or closing parenthesis in EXPLAIN options")),
+            }
+        }
+    } else {
+        if let Some((_, Keyword(k))) = stream.peek() {
+            if *k == Keyword::Analyze {
+                stream.next();
+                analyze = true;
+            }
+        }
+        if let Some((_, Keyword(k))) = stream.peek() {
+            if *k == Keyword::Verbose {
+                stream.next();
+                verbose = true;
+            }
+        }
+    }
+
+    // A plan of a plan is meaningless, so EXPLAIN EXPLAIN is an error rather than nesting.
+    if let Some((pos, token @ Keyword(Keyword::Explain))) = stream.peek() {
+        return Err(ParseError::unexpected(token, pos, "a statement other than EXPLAIN to explain"));
+    }
+    let statement = parse_statement(stream, options)?;
+    Ok(Statement::Explain {
+        analyze,
+        verbose,
+        buffers,
+        format,
+        statement: Box::new(statement),
+    })
+}
+
+// Reads the optional boolean after an EXPLAIN or COPY option; a bare option means TRUE.
+fn parse_explain_flag<'a>(stream: &mut TokenStream<'a>) -> bool {
+    if let Some((_, BoolLiteral(value))) = stream.peek() {
+        let value = *value;
+        stream.next();
+        return value;
+    }
+    true
+}
+
+// Parses `BEGIN [WORK | TRANSACTION] [ISOLATION LEVEL ...]`; BEGIN has already been consumed.
+fn parse_begin_statement<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    skip_work_or_transaction(stream);
+    parse_transaction_modes(stream)
+}
+
+// Parses the optional `ISOLATION LEVEL ...` that ends BEGIN or START TRANSACTION.
+fn parse_transaction_modes<'a>(stream: &mut TokenStream<'a>) -> Result<Statement, ParseError> {
+    let mut isolation_level = None;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Isolation {
+            stream.next();
+            isolation_level = Some(parse_isolation_level(stream)?);
+        }
+    }
+    Ok(Statement::BeginTransaction { isolation_level })
+}
+
+// Consumes the optional noise word after BEGIN, COMMIT or ROLLBACK. WORK is not
+// reserved, so it is matched by name.
+fn skip_work_or_transaction<'a>(stream: &mut TokenStream<'a>) {
+    match stream.peek() {
+        Some((_, Keyword(Keyword::Transaction))) => {
+            stream.next();
+        }
+        Some((_, Identifier(word))) if word.eq_ignore_ascii_case("WORK") => {
+            stream.next();
+        }
+        _ => {}
+    }
+}
+
+// Parses the level after ISOLATION: `LEVEL { READ UNCOMMITTED | READ COMMITTED |
+// REPEATABLE READ | SERIALIZABLE }`. LEVEL and READ are not reserved and are
+// matched by name.
+fn parse_isolation_level<'a>(stream: &mut TokenStream<'a>) -> Result<IsolationLevel, ParseError> {
+    match stream.next() {
+        Some((_, Identifier(word))) if word.eq_ignore_ascii_case("LEVEL") => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "LEVEL after ISOLATION")),
+        None => return Err(ParseError::eof("LEVEL after ISOLATION")),
+    }
+    let expected = "READ UNCOMMITTED, READ COMMITTED, REPEATABLE READ or SERIALIZABLE";
+    let (pos, first) = stream.next().ok_or_else(|| ParseError::eof(expected))?;
+    match (first, stream.peek()) {
+        (Identifier(word), Some((_, Keyword(Keyword::Uncommitted)))) if word.eq_ignore_ascii_case("READ") => {
+            stream.next();
+            Ok(IsolationLevel::ReadUncommitted)
+        }
+        (Identifier(word), Some((_, Keyword(Keyword::Committed)))) if word.eq_ignore_ascii_case("READ") => {
+            stream.next();
+            Ok(IsolationLevel::ReadCommitted)
+        }
+        (Keyword(Keyword::Repeatable), Some((_, Identifier(word)))) if word.eq_ignore_ascii_case("READ") => {
+            stream.next();
+            Ok(IsolationLevel::RepeatableRead)
+        }
+        (Keyword(Keyword::Serializable), _) => Ok(IsolationLevel::Serializable),
+        _ => Err(ParseError::unexpected(first, pos, expected)),
+    }
+}
+
+// Consumes an optional `AND [NO] CHAIN` after COMMIT or ROLLBACK; CHAIN is matched by name.
+fn parse_and_chain<'a>(stream: &mut TokenStream<'a>) -> Result<bool, ParseError> {
+    if let Some((_, Operator(op))) = stream.peek() {
+        if op == "AND" {
+            stream.next();
+            let mut chain = true;
+            if let Some((_, Keyword(k))) = stream.peek() {
+                if *k == Keyword::No {
+                    stream.next();
+                    chain = false;
+                }
+            }
+            return match stream.next() {
+                Some((_, Identifier(word))) if word.eq_ignore_ascii_case("CHAIN") => Ok(chain),
+                Some((pos, token)) => Err(ParseError::unexpected(token, pos, "CHAIN after AND")),
+                None => Err(ParseError::eof("CHAIN after AND")),
+            };
+        }
+    }
+    Ok(false)
+}
+
+// Consumes an optional `IF EXISTS` and reports whether it was present.
+fn parse_if_exists<'a>(stream: &mut TokenStream<'a>) -> Result<bool, ParseError> {
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::If {
+            stream.next();
+            return match stream.next() {
+                Some((_, Keyword(Keyword::Exists))) => Ok(true),
+                Some((pos, token)) => Err(ParseError::unexpected(token, pos, "EXISTS after IF")),
+                None => Err(ParseError::eof("EXISTS after IF")),
+            };
+        }
+    }
+    Ok(false)
+}
+
+// Consumes an optional `IF NOT EXISTS` and reports whether it was present.
+fn parse_if_not_exists<'a>(stream: &mut TokenStream<'a>) -> Result<bool, ParseError> {
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::If {
+            stream.next();
+            expect_tokens(stream, &[Keyword(Keyword::Not), Keyword(Keyword::Exists)], "NOT EXISTS after IF")?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// Parses a SELECT starting at `tokens[0]` for use inside an expression nested
+// `depth` levels deep and returns it with the number of tokens consumed.
+pub(crate) fn parse_subquery(tokens: &[Token], options: &ParserOptions, depth: usize) -> Result<(Statement, usize), ParseError> {
+    let depth = depth + QUERY_NESTING_COST;
+    if depth > MAX_EXPRESSION_DEPTH {
+        return Err(ParseError::InvalidExpression {
+            reason: "Subqueries are nested too deeply".to_string(),
+            pos: 0,
+        });
+    }
+    let mut stream = TokenStream { tokens, pos: 0, depth };
+    match stream.next() {
+        Some((_, Keyword(Keyword::Select))) => {}
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "SELECT in subquery")),
+        None => return Err(ParseError::eof("SELECT in subquery")),
+    }
+    let statement = parse_select_statement(&mut stream, options)?;
+    Ok((statement, stream.index()))
+}
+
+// Parses a table reference after FROM: a table name or a parenthesized SELECT,
+// each followed by an alias (optional for tables, required for subqueries).
+fn parse_table_ref<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+) -> Result<TableRef, ParseError> {
+    let lateral = matches!(stream.peek(), Some((_, Keyword(Keyword::Lateral))));
+    if lateral {
+        stream.next();
+    }
+
+    match stream.next() {
+        // LATERAL can only be applied to a subquery.
+        Some((pos, token @ Identifier(_))) if lateral => Err(ParseError::unexpected(token, pos, "subquery after LATERAL")),
+        Some((_, Identifier(name))) => Ok(TableRef::Table {
+            name: name.to_string(),
+            alias: parse_table_alias(stream)?,
+            tablesample: parse_tablesample(stream, options)?,
+        }),
+        Some((pos, LParen)) => {
+            if stream.depth + QUERY_NESTING_COST > MAX_EXPRESSION_DEPTH {
+                return Err(ParseError::InvalidExpression {
+                    reason: "Derived tables are nested too deeply".to_string(),
+                    pos,
+                });
+            }
+            stream.depth += QUERY_NESTING_COST;
+            let query = match stream.next() {
+                Some((_, Keyword(Keyword::Select))) => parse_select_statement(stream, options)?,
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "SELECT in derived table")),
+                None => return Err(ParseError::eof("SELECT in derived table")),
+            };
+            stream.depth -= QUERY_NESTING_COST;
+            expect_tokens(stream, &[RParen], "closing parenthesis after derived table")?;
+            match parse_table_alias(stream)? {
+                Some(alias) => Ok(TableRef::Derived {
+                    query: Box::new(query),
+                    alias,
+                    lateral,
+                }),
+                None => Err(match stream.peek() {
+                    Some((pos, token)) => ParseError::unexpected(token, pos, "alias for derived table"),
+                    None => ParseError::eof("alias for derived table"),
+                }),
+            }
+        }
+        Some((pos, token)) => Err(ParseError::expected_name(token, pos, "table name after FROM")),
+        None => Err(ParseError::eof("table name after FROM")),
+    }
+}
+
+// Parses a SELECT column that starts at the already-consumed token `start`, then
+// advances the cursor past the rest of it.
+fn parse_column_expression<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+    start: usize,
+    what: &str,
+) -> Result<Expression, ParseError> {
+    stream.seek(start);
+    parse_clause_expression(stream, options, what)
+}
+
+// Wraps a SELECT column in the `[AS] alias` that follows it, if any. A bare
+// name that looks like a misspelled FROM is left for the column loop to report.
+fn parse_column_alias<'a>(stream: &mut TokenStream<'a>, column: Expression) -> Result<Expression, ParseError> {
+    let alias = match stream.peek() {
+        Some((_, Keyword(Keyword::As))) => {
+            stream.next();
+            match stream.next() {
+                Some((_, Identifier(alias))) => alias.to_string(),
+                Some((pos, token)) => return Err(ParseError::expected_name(token, pos, "column alias after AS")),
+                None => return Err(ParseError::eof("column alias after AS")),
+            }
+        }
+        Some((_, Identifier(alias))) if suggest_keyword(alias, &[Keyword::From]).is_none() => {
+            stream.next();
+            alias.to_string()
+        }
+        _ => return Ok(column),
+    };
+    Ok(Expression::Aliased {
+        expr: Box::new(column),
+        alias,
+    })
+}
+
+// Parses an optional TABLESAMPLE clause after a table name and its alias.
+fn parse_tablesample<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions) -> Result<Option<TableSample>, ParseError> {
+    match stream.peek() {
+        Some((_, Keyword(Keyword::Tablesample))) => {
+            stream.next();
+        }
+        _ => return Ok(None),
+    }
+    let method = match stream.next() {
+        Some((_, Keyword(k @ Keyword::Bernoulli))) => k.to_string(),
+        // SYSTEM is not reserved; it is spelled in upper case like BERNOULLI.
+        Some((_, Identifier(name))) if name.eq_ignore_ascii_case("SYSTEM") => "SYSTEM".to_string(),
+        Some((_, Identifier(name))) => name.to_string(),
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "sampling method after TABLESAMPLE")),
+        None => return Err(ParseError::eof("sampling method after TABLESAMPLE")),
+    };
+    let percent = parse_parenthesized_expression(stream, options, "TABLESAMPLE percentage")?;
+
+    let mut seed = None;
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Repeatable {
+            stream.next();
+            seed = Some(parse_parenthesized_expression(stream, options, "REPEATABLE seed")?);
+        }
+    }
+
+    Ok(Some(TableSample { method, percent, seed }))
+}
+
+// Parses `( expr )`, consuming both parentheses.
+fn parse_parenthesized_expression<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+    clause: &str,
+) -> Result<Expression, ParseError> {
+    expect_tokens(stream, &[LParen], &format!("opening parenthesis before {}", clause))?;
+    let expr = parse_clause_expression(stream, options, clause)?;
+    expect_tokens(stream, &[RParen], &format!("closing parenthesis after {}", clause))?;
+    Ok(expr)
+}
+
+// Consumes an optional `[AS] alias` after a table reference.
+fn parse_table_alias<'a>(stream: &mut TokenStream<'a>) -> Result<Option<String>, ParseError> {
+    match stream.peek() {
+        Some((_, Keyword(Keyword::As))) => {
+            stream.next();
+            match stream.next() {
+                Some((_, Identifier(alias))) => Ok(Some(alias.to_string())),
+                Some((pos, token)) => Err(ParseError::unexpected(token, pos, "alias after AS")),
+                None => Err(ParseError::eof("alias after AS")),
+            }
+        }
+        Some((_, Identifier(alias))) => {
+            let alias = alias.to_string();
+            stream.next();
+            Ok(Some(alias))
+        }
+        _ => Ok(None),
+    }
+}
+
+// Parses one expression starting at the cursor and advances the cursor past every
+// token the expression consumed.
+fn parse_clause_expression<'a>(
+    stream: &mut TokenStream<'a>,
+    options: &ParserOptions,
+    clause: &str,
+) -> Result<Expression, ParseError> {
+    let start = stream.index();
+    let (expr, consumed) = crate::expression::parse_expression_at_depth(stream.remaining(), 0, options, stream.depth)
+        .map_err(|e| e.offset(start).context(format!("Error parsing {}", clause)))?;
+    stream.advance(consumed);
+    Ok(expr)
+}
+
+// Consumes an optional ASC/DESC after a sort expression.
+fn parse_order_direction<'a>(stream: &mut TokenStream<'a>) -> Option<Order> {
+    match stream.peek() {
+        Some((_, Asc)) => {
+            stream.next();
+            Some(Order::Asc)
+        }
+        Some((_, Desc)) => {
+            stream.next();
+            Some(Order::Desc)
+        }
+        _ => None,
+    }
+}
+
+// ORDER BY accepts a bare integer as a 1-based position in the select list
+// (`ORDER BY 2 DESC`). Positions are kept as `Expression::Number`, so check here
+// that one at `pos` refers to an existing column. A `*` in the select list makes
+// the column count unknown, in which case only the lower bound is checked.
+fn validate_order_by_ordinal(columns: &[Expression], expr: &Expression, pos: usize) -> Result<(), ParseError> {
+    let has_wildcard = columns
+        .iter()
+        .any(|column| match column {
+            Expression::Identifier(name) => name == "*",
+            Expression::QualifiedStar(_) => true,
+            _ => false,
+        });
+    if let Expression::Number(position) = expr {
+        if *position < 1 || (!has_wildcard && *position as usize > columns.len()) {
+            return Err(ParseError::InvalidExpression {
+                reason: format!("ORDER BY position {} is not in select list (1..{})", position, columns.len()),
+                pos,
+            });
+        }
+    }
+    Ok(())
+}
+
+// Parses the body of a `FOR UPDATE | NO KEY UPDATE | SHARE | KEY SHARE [OF t, ...]
+// [NOWAIT | SKIP LOCKED]` clause; the FOR keyword has already been consumed.
+fn parse_locking_clause<'a>(stream: &mut TokenStream<'a>) -> Result<LockingClause, ParseError> {
+    let strength = match stream.next() {
+        Some((_, Keyword(Keyword::Update))) => LockStrength::Update,
+        Some((_, Keyword(Keyword::Share))) => LockStrength::Share,
+        Some((_, Keyword(Keyword::No))) => {
+            expect_tokens(stream, &[Keyword(Keyword::Key), Keyword(Keyword::Update)], "KEY UPDATE after FOR NO")?;
+            LockStrength::NoKeyUpdate
+        }
+        Some((_, Keyword(Keyword::Key))) => match stream.next() {
+            Some((_, Keyword(Keyword::Share))) => LockStrength::KeyShare,
+            Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "SHARE after FOR KEY")),
+            None => return Err(ParseError::eof("SHARE after FOR KEY")),
+        },
+        Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "UPDATE or SHARE after FOR")),
+        None => return Err(ParseError::eof("UPDATE or SHARE after FOR")),
+    };
+
+    let mut of_tables = Vec::new();
+    if let Some((_, Keyword(k))) = stream.peek() {
+        if *k == Keyword::Of {
+            stream.next(); // Consume OF
+            loop {
+                match stream.next() {
+                    Some((_, Identifier(name))) => of_tables.push(name.to_string()),
+                    Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "table name after OF")),
+                    None => return Err(ParseError::eof("table name after OF")),
+                }
+                if let Some((_, Comma)) = stream.peek() {
+                    stream.next();
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+
+    let wait = match stream.peek() {
+        Some((_, Keyword(Keyword::Nowait))) => {
+            stream.next();
+            LockWait::NoWait
+        }
+        Some((_, Keyword(Keyword::Skip))) => {
+            stream.next();
+            match stream.next() {
+                Some((_, Keyword(Keyword::Locked))) => LockWait::SkipLocked,
+                Some((pos, token)) => return Err(ParseError::unexpected(token, pos, "LOCKED after SKIP")),
+                None => return Err(ParseError::eof("LOCKED after SKIP")),
+            }
+        }
+        _ => LockWait::Wait,
+    };
+
+    Ok(LockingClause {
+        strength,
+        of_tables,
+        wait,
+    })
+}
+
+// Parses the row count of a LIMIT or OFFSET clause, rejecting negative literals.
+fn parse_limit_value<'a>(stream: &mut TokenStream<'a>, options: &ParserOptions, clause: &str) -> Result<Expression, ParseError> {
+    match stream.peek() {
+        Some((pos, token @ (Eof | Semicolon))) => {
+            return Err(ParseError::unexpected(token, pos, &format!("a value after {}", clause)));
+        }
+        None => return Err(ParseError::eof(&format!("a value after {}", clause))),
+        Some((pos, token @ Operator(op))) if op == "-" => {
+            return Err(ParseError::unexpected(token, pos, &format!("non-negative {} value", clause)));
+        }
+        _ => {}
+    }
+    parse_clause_expression(stream, options, &format!("{} clause", clause))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::{tokenize, PlaceholderKind};
+
+    fn parse_sql(sql: &str) -> Result<Statement, ParseError> {
+        parse(&tokenize(sql))
+    }
+
+    fn expr(sql: &str) -> Expression {
+        crate::expression::parse_expression(&tokenize(sql), 0).unwrap().0
+    }
+
+    // The type of the only column of `CREATE TABLE t (c <type>)`.
+    fn column_type(type_name: &str) -> DBType {
+        match parse_sql(&format!("CREATE TABLE t (c {})", type_name)) {
+            Ok(Statement::CreateTable { column_list, .. }) => column_list[0].column_type.clone(),
+            other => panic!("expected CREATE TABLE, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn uuid_column() {
+        assert_eq!(column_type("UUID"), DBType::Uuid);
+    }
+
+    #[test]
+    fn json_column() {
+        assert_eq!(column_type("JSON"), DBType::Json);
+    }
+
+    #[test]
+    fn jsonb_column() {
+        assert_eq!(column_type("jsonb"), DBType::Jsonb);
+    }
+
+    #[test]
+    fn xml_column() {
+        assert_eq!(column_type("XML"), DBType::Xml);
+    }
+
+    #[test]
+    fn bytea_column() {
+        assert_eq!(column_type("BYTEA"), DBType::Bytea);
+    }
+
+    #[test]
+    fn postgres_types_with_constraints() {
+        let stmt = parse_sql("CREATE TABLE docs (id UUID PRIMARY KEY, body JSONB NOT NULL, raw BYTEA)").unwrap();
+        match stmt {
+            Statement::CreateTable { column_list, .. } => {
+                let types: Vec<_> = column_list.iter().map(|c| c.column_type.clone()).collect();
+                assert_eq!(types, vec![DBType::Uuid, DBType::Jsonb, DBType::Bytea]);
+                assert_eq!(column_list[0].constraints, vec![Constraint::PrimaryKey]);
+                assert_eq!(column_list[1].constraints, vec![Constraint::NotNull]);
+            }
+            other => panic!("expected CREATE TABLE, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn empty_column_list_is_an_error() {
+        assert_eq!(
+            parse_sql("CREATE TABLE t ()"),
+            Err(ParseError::MalformedColumnList {
+                table: "t".to_string(),
+                problem: ColumnListProblem::Empty,
+                pos: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn trailing_comma_in_column_list_is_an_error() {
+        assert_eq!(
+            parse_sql("CREATE TABLE t (a INT, b INT,)"),
+            Err(ParseError::MalformedColumnList {
+                table: "t".to_string(),
+                problem: ColumnListProblem::TrailingComma,
+                pos: 10,
+            })
+        );
+    }
+
+    #[test]
+    fn unclosed_column_list_is_an_error() {
+        let unclosed = Err(ParseError::MalformedColumnList {
+            table: "t".to_string(),
+            problem: ColumnListProblem::Unclosed,
+            pos: 3,
+        });
+        assert_eq!(parse_sql("CREATE TABLE t (a INT"), unclosed);
+        assert_eq!(parse_sql("CREATE TABLE t (a INT,"), unclosed);
+        assert_eq!(parse_sql("CREATE TABLE t ("), unclosed);
+    }
+
+    #[test]
+    fn serial_primary_key() {
+        match parse_sql("CREATE TABLE users (id SERIAL PRIMARY KEY, n BIGSERIAL, m smallserial)").unwrap() {
+            Statement::CreateTable { column_list, .. } => {
+                let types: Vec<_> = column_list.iter().map(|c| c.column_type.clone()).collect();
+                assert_eq!(types, vec![DBType::Serial, DBType::BigSerial, DBType::SmallSerial]);
+                // SERIAL implies NOT NULL without the parser adding it.
+                assert_eq!(column_list[0].constraints, vec![Constraint::PrimaryKey]);
+                assert!(column_list[1].constraints.is_empty());
+            }
+            other => panic!("expected CREATE TABLE, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_column_type_points_at_the_type() {
+        assert_eq!(
+            parse_sql("CREATE TABLE t (a FOO)"),
+            Err(ParseError::UnexpectedToken {
+                found: Identifier(Symbol::from("FOO")),
+                expected: vec!["data type for column 'a'".to_string()],
+                pos: 5,
+            })
+        );
+        assert_eq!(
+            parse_sql("CREATE TABLE t (a"),
+            Err(ParseError::UnexpectedEof {
+                expected: vec!["data type for column 'a'".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn column_equality_ignores_token_index() {
+        let columns = |sql: &str| match parse_sql(sql) {
+            Ok(Statement::CreateTable { column_list, .. }) => column_list,
+            other => panic!("expected CREATE TABLE, got {:?}", other),
+        };
+        let compact = columns("CREATE TABLE t (a INT)");
+        let spaced = columns("CREATE TABLE IF NOT EXISTS t (a INT)");
+        assert_ne!(compact[0].token_index, spaced[0].token_index);
+        assert_eq!(compact, spaced);
+        assert_ne!(compact, columns("CREATE TABLE t (a INT NOT NULL)"));
+    }
+
+    #[test]
+    fn group_by() {
+        match parse_sql("SELECT a FROM t GROUP BY a").unwrap() {
+            Statement::Select { group_by, having, .. } => {
+                assert_eq!(group_by, vec![expr("a")]);
+                assert_eq!(having, None);
+            }
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+        match parse_sql("SELECT a, b, count(*) FROM t GROUP BY a, b + 1").unwrap() {
+            Statement::Select { group_by, .. } => assert_eq!(group_by, vec![expr("a"), expr("b + 1")]),
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn having_compares_an_aggregate() {
+        match parse_sql("SELECT dept, count(*) FROM emp GROUP BY dept HAVING count(*) > 5").unwrap() {
+            Statement::Select { group_by, having, .. } => {
+                assert_eq!(group_by, vec![expr("dept")]);
+                assert_eq!(having, Some(expr("count(*) > 5")));
+            }
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn having_with_order_by() {
+        match parse_sql("SELECT dept FROM emp GROUP BY dept HAVING max(salary) > 100 ORDER BY dept DESC").unwrap() {
+            Statement::Select { having, orderby, .. } => {
+                assert_eq!(having, Some(expr("max(salary) > 100")));
+                assert_eq!(orderby.len(), 1);
+                assert_eq!(orderby[0].expr, expr("dept"));
+                assert_eq!(orderby[0].direction, Some(Order::Desc));
+            }
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn having_without_group_by() {
+        match parse_sql("SELECT count(*) FROM t HAVING count(*) > 1").unwrap() {
+            Statement::Select { group_by, having, .. } => {
+                assert!(group_by.is_empty());
+                assert_eq!(having, Some(expr("count(*) > 1")));
+            }
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn clause_order_is_enforced() {
+        assert!(parse_sql("SELECT a FROM t ORDER BY a HAVING count(*) > 1").is_err());
+        assert!(parse_sql("SELECT a FROM t HAVING count(*) > 1 GROUP BY a").is_err());
+        assert!(parse_sql("SELECT a FROM t GROUP a").is_err());
+    }
+
+    fn parse_strict(sql: &str, options: &ParserOptions) -> Result<Statement, ParseError> {
+        parse_with_options(&tokenize(sql), options)
+    }
+
+    #[test]
+    fn subqueries_use_the_callers_options() {
+        let strict = ParserOptions { strict_commas: true, ..ParserOptions::default() };
+        let sql = "SELECT a FROM t WHERE a = (SELECT max(b),, FROM u)";
+        assert!(parse_strict(sql, &ParserOptions::default()).is_ok());
+        assert!(parse_strict(sql, &strict).is_err());
+
+        let needs_from = ParserOptions { allow_no_from: false, ..ParserOptions::default() };
+        let sql = "SELECT a FROM t WHERE a IN (SELECT 1)";
+        assert!(parse_strict(sql, &ParserOptions::default()).is_ok());
+        assert!(parse_strict(sql, &needs_from).is_err());
+        assert!(parse_strict("SELECT a FROM t WHERE EXISTS (SELECT 1)", &needs_from).is_err());
+    }
+
+    #[test]
+    fn ctes_use_the_callers_options() {
+        let strict = ParserOptions { strict_commas: true, ..ParserOptions::default() };
+        let sql = "WITH c AS (SELECT a,, b FROM t) SELECT a FROM c";
+        assert!(parse_strict(sql, &ParserOptions::default()).is_ok());
+        assert!(parse_strict(sql, &strict).is_err());
+        let sql = "WITH c AS (SELECT a FROM t WHERE a > (SELECT , min(b) FROM u)) SELECT a FROM c";
+        assert!(parse_strict(sql, &ParserOptions::default()).is_ok());
+        assert!(parse_strict(sql, &strict).is_err());
+    }
+
+    #[test]
+    fn first_and_last_are_column_names_outside_nulls() {
+        match parse_sql("SELECT first, last FROM t ORDER BY last NULLS FIRST, first nulls last").unwrap() {
+            Statement::Select { columns, orderby, .. } => {
+                assert_eq!(columns.len(), 2);
+                assert_eq!(orderby[0].expr, expr("last"));
+                assert_eq!(orderby[0].nulls, Some(NullsOrder::First));
+                assert_eq!(orderby[1].expr, expr("first"));
+                assert_eq!(orderby[1].nulls, Some(NullsOrder::Last));
+            }
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+        assert!(parse_sql("SELECT a FROM t ORDER BY a NULLS middle").is_err());
+    }
+
+    // The direction must survive sort keys of any length.
+    fn order_by(sql: &str) -> Vec<(Expression, Option<Order>)> {
+        match parse_sql(sql).unwrap() {
+            Statement::Select { orderby, .. } => orderby.into_iter().map(|item| (item.expr, item.direction)).collect(),
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn order_by_direction_after_single_token() {
+        assert_eq!(order_by("SELECT a FROM t ORDER BY a DESC"), vec![(expr("a"), Some(Order::Desc))]);
+    }
+
+    #[test]
+    fn order_by_direction_after_compound_expression() {
+        assert_eq!(order_by("SELECT a FROM t ORDER BY a + b DESC"), vec![(expr("a + b"), Some(Order::Desc))]);
+    }
+
+    #[test]
+    fn order_by_direction_after_parenthesized_key() {
+        assert_eq!(
+            order_by("SELECT a FROM t ORDER BY (a), b DESC"),
+            vec![(expr("(a)"), None), (expr("b"), Some(Order::Desc))]
+        );
+    }
+
+    #[test]
+    fn clause_keywords_end_a_select_without_from() {
+        match parse_sql("SELECT 1 LIMIT 1").unwrap() {
+            Statement::Select { from, limit, .. } => {
+                assert!(from.is_empty());
+                assert_eq!(limit, Some(Expression::Number(1)));
+            }
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+        assert!(parse_sql("SELECT 1, 2 GROUP BY 1 HAVING true").is_ok());
+        assert!(matches!(
+            parse_sql("SELECT 1 WHERE a"),
+            Err(ParseError::UnexpectedToken { found: Keyword(Keyword::Where), pos: 2, .. })
+        ));
+        assert!(matches!(
+            parse_sql("SELECT 1 ORDER BY 1"),
+            Err(ParseError::UnexpectedToken { found: Keyword(Keyword::Order), pos: 2, .. })
+        ));
+
+        let needs_from = ParserOptions { allow_no_from: false, ..ParserOptions::default() };
+        assert!(matches!(
+            parse_with_options(&tokenize("SELECT 1 LIMIT 1"), &needs_from),
+            Err(ParseError::UnexpectedToken { pos: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn reserved_word_as_select_column() {
+        match parse_sql("SELECT order FROM t") {
+            Err(ParseError::ReservedWord { word, pos, .. }) => {
+                assert_eq!(word, "ORDER");
+                assert_eq!(pos, 1);
+            }
+            other => panic!("expected a reserved-word error, got {:?}", other),
+        }
+        assert!(matches!(parse_sql("SELECT limit FROM t"), Err(ParseError::ReservedWord { .. })));
+        assert!(parse_sql("SELECT \"order\" FROM t").is_ok());
+    }
+
+    #[test]
+    fn quoted_reserved_words_are_names() {
+        match parse_sql("CREATE TABLE \"order\" (\"check\" INT)").unwrap() {
+            Statement::CreateTable {
+                table_name, column_list, ..
+            } => {
+                assert_eq!(table_name, "order");
+                assert_eq!(column_list[0].column_name, Symbol::from("check"));
+                assert_eq!(column_list[0].column_type, DBType::Int);
+                assert!(column_list[0].constraints.is_empty());
+            }
+            other => panic!("expected CREATE TABLE, got {:?}", other),
+        }
+        match parse_sql("SELECT \"key\" FROM \"order\"").unwrap() {
+            Statement::Select { columns, from, .. } => {
+                assert_eq!(columns, vec![Expression::Identifier(Symbol::from("key"))]);
+                assert!(matches!(&from[..], [TableRef::Table { name, alias: None, .. }] if name == "order"));
+            }
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+        // Unquoted, the same words are rejected.
+        assert!(matches!(parse_sql("CREATE TABLE order (a INT)"), Err(ParseError::ReservedWord { .. })));
+        assert!(matches!(parse_sql("SELECT key FROM t"), Err(ParseError::ReservedWord { .. })));
+    }
+
+    #[test]
+    fn derived_table_with_aliased_column() {
+        match parse_sql("SELECT x FROM (SELECT a AS x FROM t WHERE a > 1) sub").unwrap() {
+            Statement::Select { columns, from, .. } => {
+                assert_eq!(columns, vec![expr("x")]);
+                match &from[..] {
+                    [TableRef::Derived { query, alias, .. }] => {
+                        assert_eq!(alias, "sub");
+                        match query.as_ref() {
+                            Statement::Select { columns, r#where, .. } => {
+                                assert_eq!(
+                                    columns,
+                                    &vec![Expression::Aliased {
+                                        expr: Box::new(expr("a")),
+                                        alias: "x".to_string(),
+                                    }]
+                                );
+                                assert_eq!(r#where, &Some(expr("a > 1")));
+                            }
+                            other => panic!("expected SELECT, got {:?}", other),
+                        }
+                    }
+                    other => panic!("expected one derived table, got {:?}", other),
+                }
+            }
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn column_alias_without_as() {
+        match parse_sql("SELECT a + 1 total, b FROM t").unwrap() {
+            Statement::Select { columns, .. } => assert_eq!(
+                columns,
+                vec![
+                    Expression::Aliased {
+                        expr: Box::new(expr("a + 1")),
+                        alias: "total".to_string(),
+                    },
+                    expr("b"),
+                ]
+            ),
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+        assert!(parse_sql("SELECT a AS FROM t").is_err());
+        assert!(parse_sql("SELECT a, c FORM t").is_err());
+    }
+
+    // Unoptimized builds need more stack than a test thread's 2 MiB to reach
+    // the nesting limit, so deep-nesting tests get the 8 MiB of a main thread.
+    fn with_main_thread_stack<F: FnOnce() + Send + 'static>(test: F) {
+        std::thread::Builder::new().stack_size(8 << 20).spawn(test).unwrap().join().unwrap();
+    }
+
+    #[test]
+    fn deeply_nested_derived_tables_are_rejected() {
+        with_main_thread_stack(|| {
+            let depth = 1000;
+            let sql = format!("SELECT a FROM {}t{}", "(SELECT a FROM ".repeat(depth), ") s".repeat(depth));
+            match parse_sql(&sql) {
+                Err(ParseError::InvalidExpression { reason, .. }) => assert_eq!(reason, "Derived tables are nested too deeply"),
+                other => panic!("expected a nesting error, got {:?}", other),
+            }
+        });
+        let sql = format!("SELECT a FROM {}t{}", "(SELECT a FROM ".repeat(2), ") s".repeat(2));
+        assert!(parse_sql(&sql).is_ok());
+    }
+
+    // The innermost error under any "Error parsing ..." context.
+    fn root_cause(mut error: ParseError) -> ParseError {
+        while let ParseError::Context { error: inner, .. } | ParseError::InStatement { error: inner, .. } = error {
+            error = *inner;
+        }
+        error
+    }
+
+    #[test]
+    fn deeply_nested_subqueries_are_rejected() {
+        with_main_thread_stack(|| {
+            let depth = 1000;
+            for sql in [
+                format!("SELECT {}1{}", "(SELECT ".repeat(depth), ")".repeat(depth)),
+                format!("SELECT a FROM t WHERE {}a = 1{}", "EXISTS (SELECT a FROM t WHERE ".repeat(depth), ")".repeat(depth)),
+                format!("SELECT a FROM t WHERE {}a = 1{}", "a IN (SELECT a FROM t WHERE ".repeat(depth), ")".repeat(depth)),
+                format!("SELECT a FROM t WHERE {}a = 1{}", "a = ANY (SELECT a FROM t WHERE ".repeat(depth), ")".repeat(depth)),
+            ] {
+                match parse_sql(&sql).map_err(root_cause) {
+                    Err(ParseError::InvalidExpression { reason, .. }) => assert_eq!(reason, "Subqueries are nested too deeply"),
+                    other => panic!("expected a nesting error, got {:?}", other),
+                }
+            }
+        });
+        let sql = format!("SELECT {}1{}", "(SELECT ".repeat(10), ")".repeat(10));
+        assert!(parse_sql(&sql).is_ok());
+    }
+
+    #[test]
+    fn transaction_statements_in_a_batch() {
+        let script = "BEGIN; CREATE TABLE t (a INT); COMMIT; \
+                      BEGIN TRANSACTION; SELECT a FROM t; ROLLBACK; \
+                      START TRANSACTION; TRUNCATE t; COMMIT WORK";
+        let statements = parse_statements(&tokenize(script)).unwrap();
+        let begin = Statement::BeginTransaction { isolation_level: None };
+        let commit = Statement::Commit { chain: false };
+        assert_eq!(statements.len(), 9);
+        for i in [0, 3, 6] {
+            assert_eq!(statements[i], begin);
+        }
+        assert_eq!(statements[2], commit);
+        assert_eq!(statements[5], Statement::Rollback { chain: false });
+        assert_eq!(statements[8], commit);
+        assert!(matches!(statements[1], Statement::CreateTable { .. }));
+        assert!(matches!(statements[4], Statement::Select { .. }));
+        assert!(matches!(statements[7], Statement::Truncate { .. }));
+
+        // START TRANSACTION and COMMIT WORK format in their short spellings.
+        let formatted: Vec<String> = statements.iter().map(ToString::to_string).collect();
+        assert_eq!(formatted[6], "BEGIN");
+        assert_eq!(formatted[8], "COMMIT");
+        assert_eq!(parse_statements(&tokenize(&formatted.join("; "))), Ok(statements));
+    }
+
+    #[test]
+    fn transaction_words_are_column_names_elsewhere() {
+        match parse_sql("SELECT level, read, work, chain FROM t").unwrap() {
+            Statement::Select { columns, .. } => {
+                assert_eq!(columns, vec![expr("level"), expr("read"), expr("work"), expr("chain")])
+            }
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+        assert_eq!(
+            parse_sql("BEGIN WORK ISOLATION LEVEL READ COMMITTED").unwrap(),
+            Statement::BeginTransaction {
+                isolation_level: Some(IsolationLevel::ReadCommitted),
+            }
+        );
+        assert_eq!(
+            parse_sql("begin isolation level repeatable read").unwrap(),
+            Statement::BeginTransaction {
+                isolation_level: Some(IsolationLevel::RepeatableRead),
+            }
+        );
+        assert_eq!(parse_sql("COMMIT WORK AND NO CHAIN").unwrap(), Statement::Commit { chain: false });
+        assert_eq!(parse_sql("ROLLBACK AND CHAIN").unwrap(), Statement::Rollback { chain: true });
+        assert!(parse_sql("COMMIT AND LINK").is_err());
+    }
+
+    #[test]
+    fn format_is_a_column_name_outside_options() {
+        match parse_sql("SELECT format FROM files").unwrap() {
+            Statement::Select { columns, .. } => assert_eq!(columns, vec![expr("format")]),
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+        match parse_sql("EXPLAIN (ANALYZE, FORMAT JSON) SELECT format FROM files").unwrap() {
+            Statement::Explain { analyze, format, .. } => {
+                assert!(analyze);
+                assert_eq!(format, Some(ExplainFormat::Json));
+            }
+            other => panic!("expected EXPLAIN, got {:?}", other),
+        }
+        match parse_sql("COPY files FROM STDIN (format csv)").unwrap() {
+            Statement::Copy { options, .. } => assert_eq!(options, vec![CopyOption::Format("csv".to_string())]),
+            other => panic!("expected COPY, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn explain_forms() {
+        let explain = |analyze, verbose, buffers, format| Statement::Explain {
+            analyze,
+            verbose,
+            buffers,
+            format,
+            statement: Box::new(parse_sql("SELECT a FROM t").unwrap()),
+        };
+        assert_eq!(parse_sql("EXPLAIN SELECT a FROM t"), Ok(explain(false, false, false, None)));
+        assert_eq!(parse_sql("EXPLAIN ANALYZE SELECT a FROM t"), Ok(explain(true, false, false, None)));
+        assert_eq!(parse_sql("EXPLAIN ANALYZE VERBOSE SELECT a FROM t"), Ok(explain(true, true, false, None)));
+        assert_eq!(
+            parse_sql("EXPLAIN (ANALYZE, VERBOSE FALSE, BUFFERS, FORMAT YAML) SELECT a FROM t"),
+            Ok(explain(true, false, true, Some(ExplainFormat::Yaml)))
+        );
+        assert_eq!(
+            parse_sql("EXPLAIN (FORMAT CSV) SELECT a FROM t"),
+            Err(ParseError::UnexpectedToken {
+                found: Identifier(Symbol::from("CSV")),
+                expected: vec!["TEXT, JSON, XML or YAML after FORMAT".to_string()],
+                pos: 3,
+            })
+        );
+        assert!(matches!(parse_sql("EXPLAIN EXPLAIN SELECT a FROM t"), Err(ParseError::UnexpectedToken { pos: 1, .. })));
+    }
+
+    #[test]
+    fn set_scope_words_are_names_elsewhere() {
+        assert_eq!(
+            parse_sql("SET SESSION search_path TO public").unwrap(),
+            Statement::Set {
+                variable: "search_path".to_string(),
+                values: vec![expr("public")],
+                scope: Some(SetScope::Session),
+            }
+        );
+        assert_eq!(
+            parse_sql("set global max_connections = 10").unwrap(),
+            Statement::Set {
+                variable: "max_connections".to_string(),
+                values: vec![expr("10")],
+                scope: Some(SetScope::Global),
+            }
+        );
+        assert_eq!(
+            parse_sql("SET local = 1").unwrap(),
+            Statement::Set {
+                variable: "local".to_string(),
+                values: vec![expr("1")],
+                scope: None,
+            }
+        );
+        match parse_sql("SELECT session, global, local FROM t").unwrap() {
+            Statement::Select { columns, .. } => assert_eq!(columns.len(), 3),
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn all_in_grant_and_set_operations() {
+        match parse_sql("GRANT ALL PRIVILEGES ON ALL TABLES IN SCHEMA public TO alice").unwrap() {
+            Statement::Grant { privileges, on, .. } => {
+                assert_eq!(privileges, vec![Privilege::All]);
+                assert_eq!(on, GrantObject::AllTablesInSchema("public".to_string()));
+            }
+            other => panic!("expected GRANT, got {:?}", other),
+        }
+        match parse_sql("GRANT SELECT ON all TO alice").unwrap() {
+            Statement::Grant { on, .. } => assert_eq!(on, GrantObject::Table("all".to_string())),
+            other => panic!("expected GRANT, got {:?}", other),
+        }
+        match parse_sql("SELECT a FROM t UNION ALL SELECT all FROM u").unwrap() {
+            Statement::SetOperation { op, all, right, .. } => {
+                assert_eq!(op, SetOp::Union);
+                assert!(all);
+                match *right {
+                    Statement::Select { columns, .. } => assert_eq!(columns, vec![expr("all")]),
+                    other => panic!("expected SELECT, got {:?}", other),
+                }
+            }
+            other => panic!("expected UNION, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn order_by_after_union_sorts_the_whole_result() {
+        match parse_sql("SELECT a FROM t UNION SELECT b FROM u ORDER BY 1").unwrap() {
+            Statement::SetOperation { orderby, right, .. } => {
+                assert_eq!(
+                    orderby,
+                    vec![OrderByExpr {
+                        expr: Expression::Number(1),
+                        direction: None,
+                        nulls: None,
+                    }]
+                );
+                match *right {
+                    Statement::Select { orderby, .. } => assert!(orderby.is_empty()),
+                    other => panic!("expected SELECT, got {:?}", other),
+                }
+            }
+            other => panic!("expected UNION, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn chained_unions_associate_to_the_left() {
+        let select = |sql| parse_sql(sql).unwrap();
+        let union = |all, left, right| Statement::SetOperation {
+            op: SetOp::Union,
+            all,
+            left: Box::new(left),
+            right: Box::new(right),
+            orderby: vec![],
+            limit: None,
+            offset: None,
+        };
+        assert_eq!(
+            parse_sql("SELECT a FROM t UNION ALL SELECT b FROM u UNION SELECT c FROM v UNION ALL SELECT d FROM w"),
+            Ok(union(
+                true,
+                union(
+                    false,
+                    union(true, select("SELECT a FROM t"), select("SELECT b FROM u")),
+                    select("SELECT c FROM v")
+                ),
+                select("SELECT d FROM w")
+            ))
+        );
+    }
+
+    #[test]
+    fn system_is_a_name_outside_tablesample() {
+        match parse_sql("SELECT system FROM t TABLESAMPLE system (10)").unwrap() {
+            Statement::Select { columns, from, .. } => {
+                assert_eq!(columns, vec![expr("system")]);
+                match &from[..] {
+                    [TableRef::Table {
+                        tablesample: Some(sample), ..
+                    }] => {
+                        assert_eq!(sample.method, "SYSTEM");
+                        assert_eq!(sample.percent, expr("10"));
+                    }
+                    other => panic!("expected a sampled table, got {:?}", other),
+                }
+            }
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn comment_and_column_are_names_elsewhere() {
+        match parse_sql("SELECT comment, column FROM posts").unwrap() {
+            Statement::Select { columns, .. } => assert_eq!(columns, vec![expr("comment"), expr("column")]),
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+        assert_eq!(
+            parse_sql("COMMENT ON COLUMN posts.comment IS 'body text'").unwrap(),
+            Statement::Comment {
+                object: CommentObject::Column("posts".to_string(), "comment".to_string()),
+                value: Some("body text".to_string()),
+            }
+        );
+        match parse_sql("CREATE TABLE posts (comment VARCHAR COMMENT 'body' NOT NULL) COMMENT 'all posts'").unwrap() {
+            Statement::CreateTable { column_list, comment, .. } => {
+                assert_eq!(column_list[0].column_name, "comment");
+                assert_eq!(column_list[0].comment, Some("body".to_string()));
+                assert_eq!(column_list[0].constraints, vec![Constraint::NotNull]);
+                assert_eq!(comment, Some("all posts".to_string()));
+            }
+            other => panic!("expected CREATE TABLE, got {:?}", other),
+        }
+        match parse_sql("ALTER TABLE posts RENAME COLUMN comment TO body").unwrap() {
+            Statement::AlterTable { action, .. } => assert_eq!(
+                action,
+                AlterAction::RenameColumn {
+                    from: "comment".to_string(),
+                    to: "body".to_string(),
+                }
+            ),
+            other => panic!("expected ALTER TABLE, got {:?}", other),
+        }
+        match parse_sql("ALTER TABLE posts RENAME column TO body").unwrap() {
+            Statement::AlterTable { action, .. } => assert_eq!(
+                action,
+                AlterAction::RenameColumn {
+                    from: "column".to_string(),
+                    to: "body".to_string(),
+                }
+            ),
+            other => panic!("expected ALTER TABLE, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn type_and_range_are_column_names() {
+        match parse_sql("SELECT type, range FROM t").unwrap() {
+            Statement::Select { columns, .. } => assert_eq!(columns, vec![expr("type"), expr("range")]),
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+        match parse_sql("CREATE TYPE mood AS ENUM ('sad', 'happy')").unwrap() {
+            Statement::CreateType { name, .. } => assert_eq!(name, "mood"),
+            other => panic!("expected CREATE TYPE, got {:?}", other),
+        }
+        match parse_sql("CREATE TYPE span AS RANGE (SUBTYPE = INT)").unwrap() {
+            Statement::CreateType { definition, .. } => assert!(matches!(definition, TypeDefinition::Range { .. })),
+            other => panic!("expected CREATE TYPE, got {:?}", other),
+        }
+        match parse_sql("ALTER TABLE t ALTER COLUMN range TYPE INT").unwrap() {
+            Statement::AlterTable { action, .. } => assert_eq!(
+                action,
+                AlterAction::AlterColumn {
+                    name: "range".to_string(),
+                    action: ColumnAlteration::SetType(DBType::Int),
+                }
+            ),
+            other => panic!("expected ALTER TABLE, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn check_constraint_qualifiers() {
+        assert!(parse_sql("CREATE TABLE items (qty INT CHECK (items.qty > 0))").is_ok());
+        assert!(parse_sql("CREATE TABLE shop.items (qty INT CHECK (items.qty > 0 AND shop.items.qty < 9))").is_ok());
+        match parse_sql("CREATE TABLE items (qty INT CHECK (other_table.qty > 0))") {
+            Err(ParseError::InvalidExpression { reason, pos: 4 }) => {
+                assert_eq!(reason, "CHECK constraint on column 'qty' at token 4 references other table 'other_table'")
+            }
+            other => panic!("expected an invalid CHECK, got {:?}", other),
+        }
+        match parse_sql("CREATE TABLE items (qty INT CHECK (price > 0))") {
+            Err(ParseError::InvalidExpression { reason, pos: 4 }) => {
+                assert_eq!(reason, "CHECK constraint on column 'qty' at token 4 references unknown column 'price'")
+            }
+            other => panic!("expected an invalid CHECK, got {:?}", other),
+        }
+        assert!(parse_sql("CREATE TABLE items (qty INT CHECK (xitems.qty > 0))").is_err());
+    }
+
+    #[test]
+    fn desc_starts_describe_only_at_statement_start() {
+        assert_eq!(
+            parse_sql("DESC users").unwrap(),
+            Statement::Describe {
+                table: "users".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_sql("DESCRIBE users").unwrap(),
+            Statement::Describe {
+                table: "users".to_string(),
+            }
+        );
+        assert_eq!(order_by("SELECT a FROM users ORDER BY a DESC"), vec![(expr("a"), Some(Order::Desc))]);
+    }
+
+    #[test]
+    fn set_data_type_with_data_as_a_column_name() {
+        match parse_sql("ALTER TABLE t ALTER COLUMN data SET DATA TYPE VARCHAR(10)").unwrap() {
+            Statement::AlterTable { action, .. } => assert_eq!(
+                action,
+                AlterAction::AlterColumn {
+                    name: "data".to_string(),
+                    action: ColumnAlteration::SetType(DBType::Varchar(10)),
+                }
+            ),
+            other => panic!("expected ALTER TABLE, got {:?}", other),
+        }
+        assert!(parse_sql("SELECT data FROM t").is_ok());
+        assert!(parse_sql("ALTER TABLE t ALTER COLUMN a SET DATA INT").is_err());
+    }
+
+    #[test]
+    fn statement_counts_and_trailing_semicolons() {
+        let count = |script: &str| parse_statements(&tokenize(script)).unwrap().len();
+        assert_eq!(count(""), 0);
+        assert_eq!(count(";;"), 0);
+        assert_eq!(count("SELECT 1"), 1);
+        assert_eq!(count("SELECT 1;"), 1);
+        assert_eq!(count("SELECT 1; SELECT 2; COMMIT"), 3);
+        assert_eq!(count("SELECT 1;; SELECT 2; COMMIT;"), 3);
+    }
+
+    #[test]
+    fn failed_statement_is_identified_by_index_and_span() {
+        let error = parse_statements(&tokenize("SELECT 1; SELECT a FROM WHERE; SELECT 2")).unwrap_err();
+        match &error {
+            ParseError::InStatement { index, span, error } => {
+                assert_eq!(*index, 1);
+                assert_eq!(*span, 3..8);
+                assert!(matches!(**error, ParseError::ReservedWord { pos: 6, .. }));
+            }
+            other => panic!("expected a statement error, got {:?}", other),
+        }
+        assert!(error.to_string().starts_with("Error in statement 2 (tokens 3..8): "));
+    }
+
+    #[test]
+    fn recovering_program_reports_the_failed_statement() {
+        let results = parse_program_recovering(&tokenize("SELECT a FROM t; SELECT a FROM WHERE; SELECT b FROM u"));
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(results[2].is_ok());
+        match &results[1] {
+            Err(ParseError::InStatement { index, span, error }) => {
+                assert_eq!((*index, span.clone()), (1, 5..10));
+                // The position counts from the start of the script, not of the statement.
+                assert!(matches!(**error, ParseError::ReservedWord { pos: 8, .. }));
+            }
+            other => panic!("expected a statement error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn output_column_count_by_statement_kind() {
+        let count = |sql: &str| parse_sql(sql).unwrap().output_column_count();
+        assert_eq!(count("SELECT a, b + 1, f(c) FROM t"), Some(3));
+        assert_eq!(count("SELECT * FROM t"), None);
+        assert_eq!(count("SELECT a, t.* FROM t"), None);
+        assert_eq!(count("SELECT a, b FROM t UNION SELECT c, d FROM u"), Some(2));
+        assert_eq!(count("WITH x AS (SELECT 1) SELECT a FROM x"), Some(1));
+        assert_eq!(count("VALUES (1, 2, 3), (4, 5, 6)"), Some(3));
+        assert_eq!(count("CREATE TABLE t (a INT)"), None);
+        assert_eq!(count("TRUNCATE t"), None);
+    }
+
+    #[test]
+    fn table_name_by_statement_kind() {
+        let table = |sql: &str| parse_sql(sql).unwrap().table_name().map(str::to_string);
+        assert_eq!(table("CREATE TABLE t (a INT)").as_deref(), Some("t"));
+        assert_eq!(table("CREATE TABLE s AS SELECT a FROM t").as_deref(), Some("s"));
+        assert_eq!(table("ALTER TABLE t RENAME a TO b").as_deref(), Some("t"));
+        assert_eq!(table("CREATE INDEX i ON t (a)").as_deref(), Some("t"));
+        assert_eq!(table("TRUNCATE t, u").as_deref(), Some("t"));
+        assert_eq!(table("COPY t FROM STDIN").as_deref(), Some("t"));
+        assert_eq!(table("COMMENT ON COLUMN t.a IS 'x'").as_deref(), Some("t"));
+        assert_eq!(table("SELECT a FROM t"), None);
+        assert_eq!(table("CREATE DATABASE d"), None);
+    }
+
+    #[test]
+    fn is_read_only_by_statement_kind() {
+        let read_only = |sql: &str| parse_sql(sql).unwrap().is_read_only();
+        assert!(read_only("SELECT a FROM t"));
+        assert!(read_only("SELECT a FROM t UNION SELECT b FROM u"));
+        assert!(read_only("EXPLAIN SELECT a FROM t"));
+        assert!(read_only("EXPLAIN ANALYZE SELECT a FROM t"));
+        assert!(read_only("COPY t TO STDOUT"));
+        assert!(read_only("BEGIN"));
+        assert!(!read_only("SELECT a FROM t FOR UPDATE"));
+        assert!(!read_only("COPY t FROM STDIN"));
+        assert!(!read_only("CREATE TABLE t (a INT)"));
+        assert!(!read_only("TRUNCATE t"));
+        assert!(!read_only("DROP DATABASE d"));
+    }
+
+    #[test]
+    fn errors_are_structured() {
+        assert!(matches!(
+            parse_sql("DROP TABLE t"),
+            Err(ParseError::UnexpectedToken { found: Keyword(Keyword::Table), pos: 1, .. })
+        ));
+        assert!(matches!(
+            parse_sql("CREATE TABLE t (a INT"),
+            Err(ParseError::MalformedColumnList {
+                problem: ColumnListProblem::Unclosed,
+                pos: 3,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse_sql("CREATE TABLE t ()"),
+            Err(ParseError::MalformedColumnList {
+                problem: ColumnListProblem::Empty,
+                pos: 4,
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse_sql("SELECT a FROM t LIMIT -1"),
+            Err(ParseError::UnexpectedToken { pos: 5, .. })
+        ));
+        assert!(matches!(
+            parse_sql("SELECT a FROM t ORDER BY 3"),
+            Err(ParseError::InvalidExpression { pos: 6, .. })
+        ));
+        assert!(matches!(
+            parse_sql("SELECT a FROM t, (SELECT 1)"),
+            Err(ParseError::UnexpectedEof { .. })
+        ));
+        assert!(matches!(
+            parse_sql("VALUES (1, 2), (3)"),
+            Err(ParseError::InvalidExpression { pos: 7, .. })
+        ));
+    }
+
+    #[test]
+    fn misspelled_keywords_are_reported_with_a_suggestion() {
+        assert_eq!(
+            parse_sql("SELEC a FROM t"),
+            Err(ParseError::Misspelled {
+                word: "SELEC".to_string(),
+                suggestion: Keyword::Select,
+                pos: 0,
+            })
+        );
+        assert!(matches!(
+            parse_sql("SELECT a FROM t WEHRE a = 1"),
+            Err(ParseError::Misspelled { suggestion: Keyword::Where, pos: 4, .. })
+        ));
+        assert!(matches!(
+            parse_sql("SELECT a FORM t"),
+            Err(ParseError::Misspelled { suggestion: Keyword::From, pos: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn recovering_parse_reports_every_broken_statement() {
+        let script = "CREATE TABLE t (a INT); SELECT FROM; SELECT a FROM t; DROP t";
+        let (statements, errors) = parse_statements_recovering(&tokenize(script));
+        assert_eq!(statements.len(), 2);
+        assert!(matches!(statements[0], Statement::CreateTable { .. }));
+        assert!(matches!(statements[1], Statement::Select { .. }));
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(&errors[0], ParseError::InStatement { index: 1, .. }));
+        assert!(matches!(&errors[1], ParseError::InStatement { index: 3, .. }));
+
+        // Without semicolons the whole input is one statement, reported once.
+        let (statements, errors) = parse_statements_recovering(&tokenize("SELECT FROM SELECT FROM"));
+        assert!(statements.is_empty());
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn create_table_as_select_with_group_by() {
+        match parse_sql("CREATE TABLE summary AS SELECT dept, count(*) FROM emp GROUP BY dept").unwrap() {
+            Statement::CreateTableAs {
+                table_name,
+                if_not_exists,
+                query,
+            } => {
+                assert_eq!(table_name, "summary");
+                assert!(!if_not_exists);
+                match *query {
+                    Statement::Select { columns, group_by, .. } => {
+                        assert_eq!(columns, vec![expr("dept"), expr("count(*)")]);
+                        assert_eq!(group_by, vec![expr("dept")]);
+                    }
+                    other => panic!("expected SELECT, got {:?}", other),
+                }
+            }
+            other => panic!("expected CREATE TABLE AS, got {:?}", other),
+        }
+    }
+
+    // The LIMIT and OFFSET of a single SELECT.
+    fn limit_and_offset(sql: &str) -> (Option<Expression>, Option<Expression>) {
+        match parse_sql(sql).unwrap() {
+            Statement::Select { limit, offset, .. } => (limit, offset),
+            other => panic!("expected SELECT, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn limit_and_offset_syntaxes() {
+        assert_eq!(
+            limit_and_offset("SELECT a FROM t LIMIT 10 OFFSET 20"),
+            (Some(Expression::Number(10)), Some(Expression::Number(20)))
+        );
+        // MySQL's `LIMIT offset, count` puts the offset first.
+        assert_eq!(
+            limit_and_offset("SELECT a FROM t LIMIT 20, 10"),
+            (Some(Expression::Number(10)), Some(Expression::Number(20)))
+        );
+        assert_eq!(
+            limit_and_offset("SELECT a FROM t ORDER BY a LIMIT ?"),
+            (Some(Expression::Placeholder(PlaceholderKind::Anonymous)), None)
+        );
+        assert_eq!(limit_and_offset("SELECT a FROM t OFFSET 5"), (None, Some(Expression::Number(5))));
+    }
+
+    #[test]
+    fn placeholders_format_as_written() {
+        let sql = "SELECT a FROM t WHERE b = $1 AND c = ? AND d = :name LIMIT ?";
+        let statement = parse_sql(sql).unwrap();
+        assert_eq!(statement.to_string(), sql);
+        assert_eq!(parse_sql(&statement.to_string()), Ok(statement));
+        assert_eq!(expr("$12").to_string(), "$12");
+        assert_eq!(expr(":user_id").to_string(), ":user_id");
+    }
+
+    #[test]
+    fn limit_rejects_negative_and_missing_values() {
+        assert_eq!(
+            parse_sql("SELECT a FROM t LIMIT -1"),
+            Err(ParseError::UnexpectedToken {
+                found: Operator("-".to_string()),
+                expected: vec!["non-negative LIMIT value".to_string()],
+                pos: 5,
+            })
+        );
+        assert!(matches!(parse_sql("SELECT a FROM t LIMIT 5 OFFSET -2"), Err(ParseError::UnexpectedToken { pos: 7, .. })));
+        assert_eq!(
+            parse_sql("SELECT a FROM t LIMIT"),
+            Err(ParseError::UnexpectedEof {
+                expected: vec!["a value after LIMIT".to_string()],
+            })
+        );
+        assert!(matches!(
+            parse_sql("SELECT a FROM t LIMIT;"),
+            Err(ParseError::UnexpectedToken { found: Semicolon, pos: 5, .. })
+        ));
+        assert!(matches!(parse_sql("SELECT a FROM t LIMIT 5 OFFSET"), Err(ParseError::UnexpectedEof { .. })));
+    }
+}
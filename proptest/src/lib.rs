@@ -0,0 +1,22 @@
+//! Property tests for the parser, built against the sources in the repository
+//! root. Run from this directory:
+//!
+//! ```text
+//! cargo test
+//! ```
+//!
+//! A failing case is shrunk to a minimal statement and its seed saved under
+//! `proptest-regressions/`, from where later runs replay it first.
+
+#[allow(dead_code)]
+#[path = "../../expression.rs"]
+mod expression;
+#[allow(dead_code)]
+#[path = "../../parser.rs"]
+mod parser;
+#[allow(dead_code)]
+#[path = "../../tokenizer.rs"]
+mod tokenizer;
+
+#[cfg(test)]
+mod round_trip;
@@ -0,0 +1,313 @@
+//! Formats randomly generated expressions and statements as SQL and checks that
+//! parsing the text gives back the same tree.
+
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+
+use crate::expression::{parse_expression, AnyOrAll, BinaryOperator, Expression, IsTarget, UnaryOperator};
+use crate::parser::{parse, ExplainFormat, NullsOrder, Order, OrderByExpr, SetOp, Statement, TableRef};
+use crate::tokenizer::{tokenize, PlaceholderKind, Symbol, Token};
+
+// Table and column names, including ones that only survive formatting quoted.
+fn name() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("a"),
+        Just("b"),
+        Just("price"),
+        Just("qty"),
+        Just("t.id"),
+        Just("order"),
+        Just("select"),
+        Just("two words"),
+        Just("42"),
+    ]
+    .prop_map(str::to_string)
+}
+
+fn binary_operator() -> impl Strategy<Value = BinaryOperator> {
+    prop_oneof![
+        Just(BinaryOperator::Or),
+        Just(BinaryOperator::And),
+        Just(BinaryOperator::Equal),
+        Just(BinaryOperator::NotEqual),
+        Just(BinaryOperator::Less),
+        Just(BinaryOperator::GreaterEqual),
+        Just(BinaryOperator::Add),
+        Just(BinaryOperator::Subtract),
+        Just(BinaryOperator::Multiply),
+        Just(BinaryOperator::Divide),
+        Just(BinaryOperator::Power),
+        Just(BinaryOperator::IsDistinctFrom),
+        Just(BinaryOperator::IsNotDistinctFrom),
+        Just(BinaryOperator::SimilarTo),
+        Just(BinaryOperator::NotSimilarTo),
+        Just(BinaryOperator::RegexMatch),
+        Just(BinaryOperator::RegexNotMatchCI),
+    ]
+}
+
+fn is_target() -> impl Strategy<Value = IsTarget> {
+    prop_oneof![Just(IsTarget::Null), Just(IsTarget::True), Just(IsTarget::False), Just(IsTarget::Unknown)]
+}
+
+fn leaf() -> impl Strategy<Value = Expression> {
+    prop_oneof![
+        (0..1000i64).prop_map(Expression::Number),
+        name().prop_map(|name| Expression::Identifier(Symbol::from(name.as_str()))),
+        "[a-z %_]{0,6}".prop_map(Expression::String),
+        any::<bool>().prop_map(Expression::Bool),
+        Just(Expression::Unknown),
+        prop_oneof![
+            (1..10usize).prop_map(PlaceholderKind::Positional),
+            Just(PlaceholderKind::Anonymous),
+            "[a-z]{1,6}".prop_map(PlaceholderKind::Named),
+        ]
+        .prop_map(Expression::Placeholder),
+        Just(Expression::Function {
+            name: "count".to_string(),
+            args: vec![Expression::Wildcard],
+            distinct: false,
+        }),
+    ]
+}
+
+// `SELECT leaf FROM table`, the query inside subquery expressions.
+fn simple_select() -> impl Strategy<Value = Statement> {
+    (leaf(), name()).prop_map(|(column, table)| Statement::Select {
+        columns: vec![column],
+        from: vec![TableRef::Table {
+            name: table,
+            alias: None,
+            tablesample: None,
+        }],
+        r#where: None,
+        group_by: vec![],
+        having: None,
+        orderby: vec![],
+        limit: None,
+        offset: None,
+        locking: None,
+    })
+}
+
+fn expression() -> impl Strategy<Value = Expression> {
+    leaf().prop_recursive(4, 48, 4, |inner| {
+        let boxed = inner.clone().prop_map(Box::new);
+        prop_oneof![
+            (boxed.clone(), binary_operator(), boxed.clone())
+                .prop_map(|(left, op, right)| Expression::BinaryOp { left, op, right }),
+            boxed.clone().prop_map(|expr| Expression::UnaryOp {
+                op: UnaryOperator::Not,
+                expr,
+            }),
+            (boxed.clone(), any::<bool>(), is_target()).prop_map(|(expr, negated, target)| Expression::IsPredicate {
+                expr,
+                negated,
+                target,
+            }),
+            (boxed.clone(), boxed.clone(), boxed.clone(), any::<bool>()).prop_map(|(expr, low, high, negated)| {
+                Expression::Between { expr, low, high, negated }
+            }),
+            (boxed.clone(), vec(inner.clone(), 1..4), any::<bool>())
+                .prop_map(|(expr, list, negated)| Expression::InList { expr, list, negated }),
+            (boxed.clone(), boxed.clone(), any::<bool>(), option::of(boxed.clone()), any::<bool>()).prop_map(
+                |(expr, pattern, negated, escape, case_insensitive)| Expression::Like {
+                    expr,
+                    pattern,
+                    negated,
+                    escape,
+                    case_insensitive,
+                }
+            ),
+            (
+                option::of(boxed.clone()),
+                vec((inner.clone(), inner.clone()), 1..3),
+                option::of(boxed.clone())
+            )
+                .prop_map(|(operand, branches, else_result)| Expression::Case {
+                    operand,
+                    branches,
+                    else_result,
+                }),
+            (prop_oneof![Just("coalesce"), Just("lower"), Just("f")], vec(inner.clone(), 1..3), any::<bool>())
+                .prop_map(|(name, args, distinct)| Expression::Function {
+                    name: name.to_string(),
+                    args,
+                    distinct,
+                }),
+            (boxed.clone(), boxed.clone()).prop_map(|(expr, zone)| Expression::AtTimeZone { expr, zone }),
+            (boxed.clone(), simple_select(), any::<bool>()).prop_map(|(expr, query, negated)| Expression::InSubquery {
+                expr,
+                subquery: Box::new(query),
+                negated,
+            }),
+            (simple_select(), any::<bool>()).prop_map(|(query, negated)| Expression::Exists {
+                subquery: Box::new(query),
+                negated,
+            }),
+            (boxed, prop_oneof![Just(AnyOrAll::Any), Just(AnyOrAll::All)], simple_select()).prop_map(
+                |(left, quantifier, query)| Expression::QuantifiedComparison {
+                    left,
+                    op: BinaryOperator::Less,
+                    quantifier,
+                    subquery: Box::new(query),
+                }
+            ),
+            simple_select().prop_map(|query| Expression::Subquery(Box::new(query))),
+        ]
+    })
+}
+
+fn column() -> impl Strategy<Value = Expression> {
+    (expression(), option::of(name())).prop_map(|(expr, alias)| match alias {
+        Some(alias) => Expression::Aliased {
+            expr: Box::new(expr),
+            alias,
+        },
+        None => expr,
+    })
+}
+
+fn table_ref() -> impl Strategy<Value = TableRef> {
+    prop_oneof![
+        (name(), option::of(name())).prop_map(|(name, alias)| TableRef::Table {
+            name,
+            alias,
+            tablesample: None,
+        }),
+        (simple_select(), name(), any::<bool>()).prop_map(|(query, alias, lateral)| TableRef::Derived {
+            query: Box::new(query),
+            alias,
+            lateral,
+        }),
+    ]
+}
+
+// A SELECT without the ORDER BY, LIMIT and OFFSET that end a whole query.
+fn select_body() -> impl Strategy<Value = Statement> {
+    (
+        vec(column(), 1..4),
+        vec(table_ref(), 1..3),
+        option::of(expression()),
+        vec(expression(), 0..3),
+        option::of(expression()),
+    )
+        .prop_map(|(columns, from, r#where, group_by, having)| Statement::Select {
+            columns,
+            from,
+            r#where,
+            group_by,
+            having,
+            orderby: vec![],
+            limit: None,
+            offset: None,
+            locking: None,
+        })
+}
+
+fn order_by() -> impl Strategy<Value = OrderByExpr> {
+    (
+        expression(),
+        option::of(prop_oneof![Just(Order::Asc), Just(Order::Desc)]),
+        option::of(prop_oneof![Just(NullsOrder::First), Just(NullsOrder::Last)]),
+    )
+        .prop_map(|(expr, direction, nulls)| OrderByExpr {
+            // A bare number is a position in the select list, which has at least one column.
+            expr: match expr {
+                Expression::Number(_) => Expression::Number(1),
+                expr => expr,
+            },
+            direction,
+            nulls,
+        })
+}
+
+// A SELECT or a chain of set operations, with the clauses that end the query.
+// INTERSECT binds tighter than UNION and EXCEPT, so it only starts the chain,
+// where a left-leaning tree reads back the same.
+fn query() -> impl Strategy<Value = Statement> {
+    (
+        select_body(),
+        vec((any::<bool>(), select_body()), 0..3),
+        prop_oneof![Just(SetOp::Intersect), Just(SetOp::Union), Just(SetOp::Except)],
+        vec(order_by(), 0..3),
+        option::of(leaf()),
+        option::of(leaf()),
+    )
+        .prop_map(|(first, rest, first_op, orderby, limit, offset)| {
+            let mut query = first;
+            for (i, (all, right)) in rest.into_iter().enumerate() {
+                let op = match (i, first_op) {
+                    (0, op) => op,
+                    (_, SetOp::Intersect) => SetOp::Union,
+                    (_, op) => op,
+                };
+                query = Statement::SetOperation {
+                    op,
+                    all,
+                    left: Box::new(query),
+                    right: Box::new(right),
+                    orderby: vec![],
+                    limit: None,
+                    offset: None,
+                };
+            }
+            match &mut query {
+                Statement::Select {
+                    orderby: o, limit: l, offset: f, ..
+                }
+                | Statement::SetOperation {
+                    orderby: o, limit: l, offset: f, ..
+                } => {
+                    *o = orderby;
+                    *l = limit;
+                    *f = offset;
+                }
+                _ => unreachable!("queries are SELECTs or set operations"),
+            }
+            query
+        })
+}
+
+fn statement() -> impl Strategy<Value = Statement> {
+    prop_oneof![
+        query(),
+        (1..4usize)
+            .prop_flat_map(|width| vec(vec(expression(), width), 1..3))
+            .prop_map(Statement::Values),
+        (
+            any::<bool>(),
+            any::<bool>(),
+            any::<bool>(),
+            option::of(prop_oneof![Just(ExplainFormat::Json), Just(ExplainFormat::Yaml)]),
+            query()
+        )
+            .prop_map(|(analyze, verbose, buffers, format, query)| Statement::Explain {
+                analyze,
+                verbose,
+                buffers,
+                format,
+                statement: Box::new(query),
+            }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn expressions_round_trip(expr in expression()) {
+        let sql = expr.to_string();
+        let tokens = tokenize(&sql);
+        let (parsed, consumed) = parse_expression(&tokens, 0)
+            .map_err(|e| TestCaseError::fail(format!("{}: {}", sql, e)))?;
+        prop_assert_eq!(&parsed, &expr, "formatted as {}", sql);
+        prop_assert!(matches!(tokens.get(consumed), None | Some(Token::Eof)), "{} left tokens over", sql);
+    }
+
+    #[test]
+    fn statements_round_trip(statement in statement()) {
+        let sql = statement.to_string();
+        let parsed = parse(&tokenize(&sql)).map_err(|e| TestCaseError::fail(format!("{}: {}", sql, e)))?;
+        prop_assert_eq!(&parsed, &statement, "formatted as {}", sql);
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use crate::parser::{parse, Statement};
+use crate::tokenizer::tokenize;
+
+// Lexical context of the scanner, carried across lines so that a semicolon is
+// only treated as a terminator outside string literals and comments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScanState {
+    Normal,
+    InString(char),
+    LineComment,
+    BlockComment,
+}
+
+/// Reads SQL from a `BufRead` and yields one parsed statement per top-level
+/// semicolon, buffering only the statement currently being read. Semicolons
+/// inside string literals and `--` / `/* */` comments do not end a statement;
+/// comments are dropped before the statement text is tokenized. Empty
+/// statements (`;;`) are skipped, and a final statement without a trailing
+/// semicolon is still returned.
+pub struct StatementReader<R: BufRead> {
+    reader: R,
+    pending: VecDeque<char>,
+    statement: String,
+    state: ScanState,
+    eof: bool,
+}
+
+impl<R: BufRead> StatementReader<R> {
+    pub fn new(reader: R) -> Self {
+        StatementReader {
+            reader,
+            pending: VecDeque::new(),
+            statement: String::new(),
+            state: ScanState::Normal,
+            eof: false,
+        }
+    }
+
+    // Scans buffered input and returns the text of the next complete statement,
+    // or None when more input is needed.
+    fn scan_statement(&mut self) -> Option<String> {
+        while let Some(c) = self.pending.pop_front() {
+            match self.state {
+                ScanState::Normal => match c {
+                    ';' => {
+                        let text = std::mem::take(&mut self.statement);
+                        if !text.trim().is_empty() {
+                            return Some(text);
+                        }
+                    }
+                    '\'' | '"' => {
+                        self.state = ScanState::InString(c);
+                        self.statement.push(c);
+                    }
+                    '-' if self.pending.front() == Some(&'-') => {
+                        self.pending.pop_front();
+                        self.state = ScanState::LineComment;
+                    }
+                    '/' if self.pending.front() == Some(&'*') => {
+                        self.pending.pop_front();
+                        self.state = ScanState::BlockComment;
+                    }
+                    _ => self.statement.push(c),
+                },
+                ScanState::InString(quote) => {
+                    self.statement.push(c);
+                    if c == quote {
+                        self.state = ScanState::Normal;
+                    }
+                }
+                ScanState::LineComment => {
+                    if c == '\n' {
+                        self.statement.push('\n');
+                        self.state = ScanState::Normal;
+                    }
+                }
+                ScanState::BlockComment => {
+                    if c == '*' && self.pending.front() == Some(&'/') {
+                        self.pending.pop_front();
+                        self.statement.push(' ');
+                        self.state = ScanState::Normal;
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<R: BufRead> Iterator for StatementReader<R> {
+    type Item = Result<Statement, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(text) = self.scan_statement() {
+                return Some(parse(&tokenize(&text)).map_err(|e| e.to_string()));
+            }
+            if self.eof {
+                let text = std::mem::take(&mut self.statement);
+                if text.trim().is_empty() {
+                    return None;
+                }
+                return Some(parse(&tokenize(&text)).map_err(|e| e.to_string()));
+            }
+
+            let mut line = String::new();
+            match self.reader.read_line(&mut line) {
+                Ok(0) => self.eof = true,
+                Ok(_) => self.pending.extend(line.chars()),
+                Err(e) => {
+                    self.eof = true;
+                    return Some(Err(format!("Error reading input: {}", e)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn read_all(input: &str) -> Vec<Result<Statement, String>> {
+        StatementReader::new(Cursor::new(input)).collect()
+    }
+
+    #[test]
+    fn splits_on_top_level_semicolons() {
+        let statements = read_all("SELECT a FROM t;\nSELECT b\n  FROM u;\n;;\nSELECT c FROM v");
+        assert_eq!(
+            statements,
+            vec![
+                parse(&tokenize("SELECT a FROM t")).map_err(|e| e.to_string()),
+                parse(&tokenize("SELECT b FROM u")).map_err(|e| e.to_string()),
+                parse(&tokenize("SELECT c FROM v")).map_err(|e| e.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn semicolons_in_strings_and_comments_do_not_split() {
+        let statements = read_all("SELECT a FROM t WHERE b = 'x;y'; -- c;d\nSELECT e /* f; */ FROM u;");
+        assert_eq!(statements.len(), 2);
+        assert_eq!(
+            statements[0],
+            parse(&tokenize("SELECT a FROM t WHERE b = 'x;y'")).map_err(|e| e.to_string())
+        );
+        assert_eq!(statements[1], parse(&tokenize("SELECT e FROM u")).map_err(|e| e.to_string()));
+    }
+
+    #[test]
+    fn a_bad_statement_does_not_stop_the_rest() {
+        let statements = read_all("SELECT a FROM t;\nSELECT FROM;\nSELECT b FROM u;\n");
+        assert_eq!(statements.len(), 3);
+        assert!(statements[0].is_ok());
+        assert!(statements[1].is_err());
+        assert!(statements[2].is_ok());
+    }
+
+    #[test]
+    fn empty_input_yields_nothing() {
+        assert!(read_all("").is_empty());
+        assert!(read_all("  ;\n-- only a comment\n").is_empty());
+    }
+}
@@ -0,0 +1,375 @@
+use crate::expression::Expression;
+use crate::parser::{CopySource, MergeAction, MergeClause, Statement, TableRef};
+
+/// The tables a statement reads or writes, deduplicated in first-seen order.
+/// Besides FROM and the target of CREATE INDEX, COPY, TRUNCATE and MERGE, the
+/// qualifier of a `t.col` column counts as a table unless it is an alias.
+pub fn referenced_tables(stmt: &Statement) -> Vec<String> {
+    let mut references = References::default();
+    references.statement(stmt);
+    references.tables
+}
+
+/// The columns a statement mentions anywhere in its expressions, without their
+/// table qualifier, deduplicated in first-seen order. `*` is not a column.
+pub fn referenced_columns(stmt: &Statement) -> Vec<String> {
+    let mut references = References::default();
+    references.statement(stmt);
+    references.columns
+}
+
+// Collects both lists in one walk. Every alias seen so far is remembered, so a
+// correlated subquery can use an alias from an enclosing FROM; names of common
+// table expressions are remembered so they are not taken for tables.
+#[derive(Default)]
+struct References {
+    tables: Vec<String>,
+    columns: Vec<String>,
+    aliases: Vec<String>,
+    ctes: Vec<String>,
+}
+
+fn push_unique(names: &mut Vec<String>, name: &str) {
+    if !names.iter().any(|existing| existing == name) {
+        names.push(name.to_string());
+    }
+}
+
+impl References {
+    fn statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Select {
+                columns,
+                from,
+                r#where,
+                group_by,
+                having,
+                orderby,
+                limit,
+                offset,
+                ..
+            } => {
+                // FROM first, so its aliases are known before any column is resolved.
+                for table in from {
+                    self.table_ref(table);
+                }
+                for column in columns {
+                    self.expression(column);
+                }
+                for expr in r#where.iter().chain(group_by).chain(having) {
+                    self.expression(expr);
+                }
+                for item in orderby {
+                    self.expression(&item.expr);
+                }
+                for expr in limit.iter().chain(offset) {
+                    self.expression(expr);
+                }
+            }
+            Statement::SetOperation {
+                left,
+                right,
+                orderby,
+                limit,
+                offset,
+                ..
+            } => {
+                self.statement(left);
+                self.statement(right);
+                for item in orderby {
+                    self.expression(&item.expr);
+                }
+                for expr in limit.iter().chain(offset) {
+                    self.expression(expr);
+                }
+            }
+            Statement::Query { ctes, body, .. } => {
+                for cte in ctes {
+                    // Registered first so a recursive CTE's reference to itself is not a table.
+                    self.ctes.push(cte.name.clone());
+                    self.statement(&cte.query);
+                }
+                self.statement(body);
+            }
+            Statement::Values(rows) => {
+                for value in rows.iter().flatten() {
+                    self.expression(value);
+                }
+            }
+            Statement::CreateView { query, .. } | Statement::CreateTableAs { query, .. } => self.statement(query),
+            Statement::CreateIndex {
+                table,
+                columns,
+                r#where,
+                ..
+            } => {
+                push_unique(&mut self.tables, table);
+                for column in columns {
+                    self.expression(&column.expr);
+                }
+                if let Some(expr) = r#where {
+                    self.expression(expr);
+                }
+            }
+            Statement::Explain { statement, .. } => self.statement(statement),
+            Statement::Copy { table_or_query, .. } => match table_or_query {
+                CopySource::Table(name, columns) => {
+                    push_unique(&mut self.tables, name);
+                    for column in columns.iter().flatten() {
+                        push_unique(&mut self.columns, column);
+                    }
+                }
+                CopySource::Query(query) => self.statement(query),
+            },
+            Statement::Truncate { tables, .. } => {
+                for table in tables {
+                    push_unique(&mut self.tables, table);
+                }
+            }
+            Statement::Merge {
+                target,
+                target_alias,
+                source,
+                on,
+                clauses,
+            } => {
+                push_unique(&mut self.tables, target);
+                if let Some(alias) = target_alias {
+                    self.aliases.push(alias.clone());
+                }
+                self.table_ref(source);
+                self.expression(on);
+                for clause in clauses {
+                    let (condition, action) = match clause {
+                        MergeClause::Matched(condition, action)
+                        | MergeClause::NotMatchedByTarget(condition, action)
+                        | MergeClause::NotMatchedBySource(condition, action) => (condition, action),
+                    };
+                    if let Some(condition) = condition {
+                        self.expression(condition);
+                    }
+                    match action {
+                        MergeAction::Update { assignments } => {
+                            for (column, value) in assignments {
+                                self.column(column);
+                                self.expression(value);
+                            }
+                        }
+                        MergeAction::Insert { columns, values } => {
+                            for column in columns {
+                                self.column(column);
+                            }
+                            for value in values {
+                                self.expression(value);
+                            }
+                        }
+                        MergeAction::Delete => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn table_ref(&mut self, table: &TableRef) {
+        match table {
+            TableRef::Table {
+                name,
+                alias,
+                tablesample,
+            } => {
+                if !self.ctes.contains(name) {
+                    push_unique(&mut self.tables, name);
+                }
+                if let Some(alias) = alias {
+                    self.aliases.push(alias.clone());
+                }
+                if let Some(sample) = tablesample {
+                    self.expression(&sample.percent);
+                    if let Some(seed) = &sample.seed {
+                        self.expression(seed);
+                    }
+                }
+            }
+            TableRef::Derived { query, alias, .. } => {
+                self.aliases.push(alias.clone());
+                self.statement(query);
+            }
+        }
+    }
+
+    // Records a possibly qualified column name such as `t.col` or `s.t.col`.
+    fn column(&mut self, name: &str) {
+        let column = match name.rsplit_once('.') {
+            Some((qualifier, column)) => {
+                self.qualifier(qualifier);
+                column
+            }
+            None => name,
+        };
+        if column != "*" {
+            push_unique(&mut self.columns, column);
+        }
+    }
+
+    // Records the table in front of `t.col` or `t.*`, unless it is an alias.
+    fn qualifier(&mut self, qualifier: &str) {
+        if !self.aliases.iter().any(|alias| alias == qualifier) {
+            push_unique(&mut self.tables, qualifier);
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier(name) => self.column(name),
+            Expression::QualifiedStar(qualifier) => self.qualifier(qualifier),
+            Expression::Subquery(subquery) | Expression::Exists { subquery, .. } => self.statement(subquery),
+            Expression::Function { args, .. } => {
+                for arg in args {
+                    self.expression(arg);
+                }
+            }
+            Expression::Aliased { expr, .. } => self.expression(expr),
+            Expression::QuantifiedComparison { left, subquery, .. } => {
+                self.expression(left);
+                self.statement(subquery);
+            }
+            Expression::InList { expr, list, .. } => {
+                self.expression(expr);
+                for item in list {
+                    self.expression(item);
+                }
+            }
+            Expression::InSubquery { expr, subquery, .. } => {
+                self.expression(expr);
+                self.statement(subquery);
+            }
+            Expression::Between { expr, low, high, .. } => {
+                self.expression(expr);
+                self.expression(low);
+                self.expression(high);
+            }
+            Expression::Like {
+                expr,
+                pattern,
+                escape,
+                ..
+            } => {
+                self.expression(expr);
+                self.expression(pattern);
+                if let Some(escape) = escape {
+                    self.expression(escape);
+                }
+            }
+            Expression::Case {
+                operand,
+                branches,
+                else_result,
+            } => {
+                if let Some(operand) = operand {
+                    self.expression(operand);
+                }
+                for (condition, result) in branches {
+                    self.expression(condition);
+                    self.expression(result);
+                }
+                if let Some(else_result) = else_result {
+                    self.expression(else_result);
+                }
+            }
+            Expression::IsPredicate { expr, .. } | Expression::UnaryOp { expr, .. } => self.expression(expr),
+            Expression::AtTimeZone { expr, zone } => {
+                self.expression(expr);
+                self.expression(zone);
+            }
+            Expression::Position { needle, haystack } => {
+                self.expression(needle);
+                self.expression(haystack);
+            }
+            Expression::Trim { chars, expr, .. } => {
+                if let Some(chars) = chars {
+                    self.expression(chars);
+                }
+                self.expression(expr);
+            }
+            Expression::Substring { expr, from, for_ } => {
+                self.expression(expr);
+                for arg in from.iter().chain(for_) {
+                    self.expression(arg);
+                }
+            }
+            Expression::Overlay {
+                string,
+                replacement,
+                from,
+                for_,
+            } => {
+                for arg in [string, replacement, from].into_iter().chain(for_) {
+                    self.expression(arg);
+                }
+            }
+            Expression::BinaryOp { left, right, .. } => {
+                self.expression(left);
+                self.expression(right);
+            }
+            Expression::Number(_)
+            | Expression::QualifiedName(_)
+            | Expression::String(_)
+            | Expression::Bool(_)
+            | Expression::Unknown
+            | Expression::Placeholder(_)
+            | Expression::Wildcard => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+    use crate::tokenizer::tokenize;
+
+    fn references(sql: &str) -> (Vec<String>, Vec<String>) {
+        let stmt = parse(&tokenize(sql)).unwrap();
+        (referenced_tables(&stmt), referenced_columns(&stmt))
+    }
+
+    #[test]
+    fn tables_and_columns_of_a_select() {
+        let (tables, columns) = references("SELECT a, t.b FROM t WHERE c > 1 AND a < 10 ORDER BY d");
+        assert_eq!(tables, vec!["t"]);
+        assert_eq!(columns, vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn aliases_are_not_tables() {
+        let (tables, columns) = references("SELECT x.a, y.* FROM orders x, (SELECT b FROM items) y");
+        assert_eq!(tables, vec!["orders", "items"]);
+        assert_eq!(columns, vec!["b", "a"]);
+    }
+
+    #[test]
+    fn qualifier_without_a_from_entry_is_a_table() {
+        let (tables, columns) = references("SELECT users.name FROM accounts");
+        assert_eq!(tables, vec!["accounts", "users"]);
+        assert_eq!(columns, vec!["name"]);
+    }
+
+    #[test]
+    fn subqueries_and_ctes() {
+        let (tables, columns) = references(
+            "WITH recent AS (SELECT id FROM orders WHERE day > 7) \
+             SELECT id FROM recent WHERE id IN (SELECT order_id FROM refunds)",
+        );
+        assert_eq!(tables, vec!["orders", "refunds"]);
+        assert_eq!(columns, vec!["id", "day", "order_id"]);
+    }
+
+    #[test]
+    fn statements_without_expressions() {
+        let (tables, columns) = references("TRUNCATE a, b");
+        assert_eq!(tables, vec!["a", "b"]);
+        assert!(columns.is_empty());
+        assert_eq!(references("BEGIN"), (vec![], vec![]));
+    }
+}
@@ -1,11 +1,21 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::iter::Peekable;
+use std::ops::Deref;
+use std::str::Chars;
+use std::sync::{Arc, OnceLock};
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
-    Keyword(String),
-    Identifier(String),
+    Keyword(Keyword),
+    Identifier(Symbol),
     Operator(String),
     Number(i64),
     StringLiteral(String),
     BoolLiteral(bool),
+    Placeholder(PlaceholderKind),
+    /// A comment's text without its delimiters, kept only with `keep_comments`.
+    Comment(String, CommentKind),
     Comma,
     Semicolon,
     LParen,
@@ -14,16 +24,358 @@ pub enum Token {
     Asc,       // For ORDER BY ASC
     Desc,      // For ORDER BY DESC
     Int,       // Data type
-    Varchar(u64), // Data type with optional length
+    Varchar(Option<u64>), // Data type with optional length
     Bool,      // Data type
     PrimaryKey,
-    NotNull,
     Check,
-    EOF,
+    Eof,
+}
+
+/// An interned identifier. Every occurrence of the same name in one call to
+/// `tokenize` shares a single allocation, so cloning a `Symbol` into the AST
+/// is a reference-count bump instead of a fresh `String`.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(Arc<str>);
+
+impl Symbol {
+    /// The identifier's text.
+    pub fn resolve(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.resolve()
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol(Arc::from(name))
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+// Formats like the underlying string, so debug output is the same as before
+// identifiers were interned.
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.resolve())
+    }
+}
+
+// The pool of identifiers seen so far by one `tokenize` call.
+#[derive(Default)]
+struct Interner {
+    names: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(existing) = self.names.get(name) {
+            return Symbol(Arc::clone(existing));
+        }
+        let name: Arc<str> = Arc::from(name);
+        self.names.insert(Arc::clone(&name));
+        Symbol(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommentKind {
+    /// `-- ...` up to the end of the line.
+    Line,
+    /// `/* ... */`
+    Block,
+}
+
+/// Toggles for the tokenizer.
+#[derive(Debug, Clone, Default)]
+pub struct TokenizerOptions {
+    /// Emit `--` and `/* */` comments as `Token::Comment` instead of dropping
+    /// them. The parser skips comment tokens either way.
+    pub keep_comments: bool,
+}
+
+/// A bind parameter in a prepared statement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlaceholderKind {
+    /// `$1`, `$2`, ...
+    Positional(usize),
+    /// `?`
+    Anonymous,
+    /// `:name`
+    Named(String),
+}
+
+impl fmt::Display for PlaceholderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlaceholderKind::Positional(n) => write!(f, "${}", n),
+            PlaceholderKind::Anonymous => write!(f, "?"),
+            PlaceholderKind::Named(name) => write!(f, ":{}", name),
+        }
+    }
+}
+
+// Renders a token as it would appear in SQL source, for error messages.
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Keyword(k) => write!(f, "{}", k),
+            Token::Identifier(name) => write!(f, "{}", name),
+            Token::Operator(op) => write!(f, "{}", op),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::StringLiteral(s) => write!(f, "'{}'", s),
+            Token::BoolLiteral(true) => write!(f, "TRUE"),
+            Token::BoolLiteral(false) => write!(f, "FALSE"),
+            Token::Placeholder(kind) => write!(f, "{}", kind),
+            Token::Comment(text, CommentKind::Line) => write!(f, "--{}", text),
+            Token::Comment(text, CommentKind::Block) => write!(f, "/*{}*/", text),
+            Token::Comma => write!(f, ","),
+            Token::Semicolon => write!(f, ";"),
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::Star => write!(f, "*"),
+            Token::Asc => write!(f, "ASC"),
+            Token::Desc => write!(f, "DESC"),
+            Token::Int => write!(f, "INT"),
+            Token::Varchar(Some(len)) => write!(f, "VARCHAR({})", len),
+            Token::Varchar(None) => write!(f, "VARCHAR"),
+            Token::Bool => write!(f, "BOOL"),
+            Token::PrimaryKey => write!(f, "PRIMARY"),
+            Token::Check => write!(f, "CHECK"),
+            Token::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+/// Formats a table, column or other name so that it tokenizes back to the same
+/// identifier: double-quoted when it is a reserved word, a number, or has
+/// characters an unquoted name cannot.
+pub(crate) struct Name<'a>(pub &'a str);
+
+impl fmt::Display for Name<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.0;
+        let bare = !name.starts_with('.')
+            && !name.ends_with('.')
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '@')
+            && lookup_word(name).is_none()
+            && name.parse::<i64>().is_err();
+        if bare && !name.is_empty() {
+            f.write_str(name)
+        } else {
+            write!(f, "\"{}\"", name.replace('"', "\"\""))
+        }
+    }
+}
+
+// Declares the `Keyword` enum together with its SQL spelling and the table of
+// every keyword, so that the three cannot drift apart.
+macro_rules! keywords {
+    ($($variant:ident => $text:literal,)*) => {
+        /// A reserved word that tokenizes as `Token::Keyword`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Keyword {
+            $($variant,)*
+        }
+
+        impl Keyword {
+            /// The keyword as spelled in SQL, in upper case.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(Keyword::$variant => $text,)*
+                }
+            }
+        }
+
+        const KEYWORDS: &[Keyword] = &[$(Keyword::$variant,)*];
+    };
+}
+
+keywords! {
+    Select => "SELECT", Distinct => "DISTINCT", From => "FROM", Where => "WHERE",
+    Having => "HAVING", Create => "CREATE", Drop => "DROP", Table => "TABLE", Order => "ORDER",
+    By => "BY", Group => "GROUP", Nulls => "NULLS", Limit => "LIMIT", Offset => "OFFSET", Not => "NOT", Is => "IS", In => "IN",
+    Between => "BETWEEN",
+    For => "FOR", Update => "UPDATE", Share => "SHARE", No => "NO", Of => "OF", Nowait => "NOWAIT",
+    Skip => "SKIP", Locked => "LOCKED",
+    Index => "INDEX", Unique => "UNIQUE", Concurrently => "CONCURRENTLY", If => "IF",
+    Exists => "EXISTS", On => "ON",
+    View => "VIEW", Replace => "REPLACE", As => "AS", With => "WITH", Option => "OPTION",
+    Database => "DATABASE", Cascade => "CASCADE", Restrict => "RESTRICT",
+    Truncate => "TRUNCATE", Restart => "RESTART", Continue => "CONTINUE", Identity => "IDENTITY",
+    Begin => "BEGIN", Transaction => "TRANSACTION", Commit => "COMMIT",
+    Rollback => "ROLLBACK", Savepoint => "SAVEPOINT",
+    Isolation => "ISOLATION", Uncommitted => "UNCOMMITTED",
+    Committed => "COMMITTED", Repeatable => "REPEATABLE", Serializable => "SERIALIZABLE",
+    Explain => "EXPLAIN", Analyze => "ANALYZE", Verbose => "VERBOSE", Buffers => "BUFFERS",
+    Show => "SHOW", Tables => "TABLES", Databases => "DATABASES", Columns => "COLUMNS",
+    Indexes => "INDEXES",
+    Like => "LIKE", Ilike => "ILIKE", Escape => "ESCAPE", Similar => "SIMILAR",
+    Set => "SET", To => "TO",
+    Grant => "GRANT", Revoke => "REVOKE", Privilege => "PRIVILEGE", Privileges => "PRIVILEGES",
+    Insert => "INSERT", Delete => "DELETE", Schema => "SCHEMA",
+    Case => "CASE", When => "WHEN", Then => "THEN", Else => "ELSE", End => "END",
+    Unknown => "UNKNOWN",
+    Lateral => "LATERAL", Tablesample => "TABLESAMPLE",
+    Bernoulli => "BERNOULLI",
+    Copy => "COPY", Stdin => "STDIN", Stdout => "STDOUT", Header => "HEADER",
+    Delimiter => "DELIMITER",
+    Merge => "MERGE", Matched => "MATCHED", Using => "USING", Into => "INTO", Values => "VALUES",
+    Sequence => "SEQUENCE", Start => "START", Increment => "INCREMENT", Minvalue => "MINVALUE",
+    Maxvalue => "MAXVALUE", Cache => "CACHE", Cycle => "CYCLE", Owned => "OWNED",
+    Union => "UNION", Intersect => "INTERSECT", Except => "EXCEPT",
+    Authorization => "AUTHORIZATION",
+    Enum => "ENUM", Subtype => "SUBTYPE",
+    Alter => "ALTER", Rename => "RENAME", Add => "ADD", Constraint => "CONSTRAINT", Describe => "DESCRIBE", Default => "DEFAULT", Recursive => "RECURSIVE",
+    Uuid => "UUID", Json => "JSON", Jsonb => "JSONB", Xml => "XML", Bytea => "BYTEA",
+    Char => "CHAR", Character => "CHARACTER", Varying => "VARYING",
+    Serial => "SERIAL", Bigserial => "BIGSERIAL", Smallserial => "SMALLSERIAL",
+    Leading => "LEADING", Trailing => "TRAILING", Both => "BOTH",
+    Key => "KEY", Null => "NULL", Placing => "PLACING",
+}
+
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// What a reserved word becomes once it has been matched.
+#[derive(Debug, Clone, Copy)]
+enum Word {
+    Keyword(Keyword),
+    Operator(&'static str),
+    Bool(bool),
+    Asc,
+    Desc,
+    Int,
+    Varchar,
+    BoolType,
+    PrimaryKey,
+    Check,
+}
+
+// No reserved word is longer than this, so longer identifiers skip the lookup.
+const MAX_WORD_LEN: usize = 16;
+
+fn words() -> &'static HashMap<&'static str, Word> {
+    static WORDS: OnceLock<HashMap<&'static str, Word>> = OnceLock::new();
+    WORDS.get_or_init(|| {
+        let mut words: HashMap<&'static str, Word> = KEYWORDS.iter().map(|&k| (k.as_str(), Word::Keyword(k))).collect();
+        words.insert("AND", Word::Operator("AND"));
+        words.insert("OR", Word::Operator("OR"));
+        words.insert("TRUE", Word::Bool(true));
+        words.insert("FALSE", Word::Bool(false));
+        words.insert("ASC", Word::Asc);
+        words.insert("DESC", Word::Desc);
+        words.insert("INT", Word::Int);
+        words.insert("VARCHAR", Word::Varchar);
+        words.insert("BOOL", Word::BoolType);
+        words.insert("PRIMARY", Word::PrimaryKey);
+        words.insert("CHECK", Word::Check);
+        debug_assert!(words.keys().all(|word| word.len() <= MAX_WORD_LEN));
+        words
+    })
+}
+
+// Reserved words are matched case-insensitively, but only over ASCII: a full
+// Unicode uppercase would turn e.g. `ſelect` into SELECT. Every reserved word is
+// made of ASCII letters, so anything else (digits, `.`, `@`, non-ASCII) is an
+// identifier without uppercasing it; the rest is uppercased on the stack.
+fn lookup_word(ident: &str) -> Option<Word> {
+    if ident.len() > MAX_WORD_LEN || !ident.bytes().all(|b| b.is_ascii_alphabetic()) {
+        return None;
+    }
+    let mut buffer = [0u8; MAX_WORD_LEN];
+    let upper = &mut buffer[..ident.len()];
+    upper.copy_from_slice(ident.as_bytes());
+    upper.make_ascii_uppercase();
+    let upper = std::str::from_utf8(upper).ok()?;
+    words().get(upper).copied()
+}
+
+// Whether the input at `chars` opens a `--` or `/*` comment.
+fn starts_comment(chars: &Peekable<Chars>) -> bool {
+    let mut ahead = chars.clone();
+    matches!((ahead.next(), ahead.next()), (Some('-'), Some('-')) | (Some('/'), Some('*')))
+}
+
+// Completes a POSIX regex match operator (`~` or `!~`), adding the `*` of its
+// case-insensitive form when one follows.
+fn regex_operator(op: &str, chars: &mut Peekable<Chars>) -> String {
+    if let Some('*') = chars.peek() {
+        chars.next();
+        format!("{}*", op)
+    } else {
+        op.to_string()
+    }
+}
+
+// Reads a double-quoted name, starting at its opening quote, onto `name`
+// together with any `.part` after it, quoted or not, so that `"s"."t"` and
+// `"t".col` each become one identifier like `s.t`. A doubled quote inside the
+// name stands for one quote.
+fn quoted_name(chars: &mut Peekable<Chars>, name: &mut String) {
+    loop {
+        chars.next(); // Consume the opening quote
+        while let Some(c) = chars.next() {
+            if c == '"' {
+                if chars.peek() != Some(&'"') {
+                    break;
+                }
+                chars.next();
+            }
+            name.push(c);
+        }
+        if chars.peek() != Some(&'.') {
+            return;
+        }
+        name.push('.');
+        chars.next();
+        if chars.peek() != Some(&'"') {
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    name.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            return;
+        }
+    }
 }
 
 pub fn tokenize(input: &str) -> Vec<Token> {
+    tokenize_with_options(input, &TokenizerOptions::default())
+}
+
+pub fn tokenize_with_options(input: &str, options: &TokenizerOptions) -> Vec<Token> {
     let mut tokens = Vec::new();
+    let mut interner = Interner::default();
     let mut chars = input.chars().peekable();
 
     while let Some(&ch) = chars.peek() {
@@ -73,11 +425,101 @@ pub fn tokenize(input: &str) -> Vec<Token> {
                 chars.next();
                 tokens.push(Token::Operator("=".to_string()));
             }
+            '?' => {
+                tokens.push(Token::Placeholder(PlaceholderKind::Anonymous));
+                chars.next();
+            }
+            '$' => {
+                chars.next();
+                let mut digits = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() {
+                        digits.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                match digits.parse::<usize>() {
+                    Ok(n) => tokens.push(Token::Placeholder(PlaceholderKind::Positional(n))),
+                    Err(_) => tokens.push(Token::Operator(format!("${}", digits))),
+                }
+            }
+            ':' => {
+                chars.next();
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' {
+                        name.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if name.is_empty() {
+                    tokens.push(Token::Operator(":".to_string()));
+                } else {
+                    tokens.push(Token::Placeholder(PlaceholderKind::Named(name)));
+                }
+            }
+            '!' => {
+                chars.next();
+                match chars.peek() {
+                    Some('=') => {
+                        chars.next();
+                        tokens.push(Token::Operator("!=".to_string()));
+                    }
+                    Some('~') => {
+                        chars.next();
+                        tokens.push(Token::Operator(regex_operator("!~", &mut chars)));
+                    }
+                    _ => tokens.push(Token::Operator("!".to_string())),
+                }
+            }
+            '~' => {
+                chars.next();
+                tokens.push(Token::Operator(regex_operator("~", &mut chars)));
+            }
+            '-' | '/' if starts_comment(&chars) => {
+                chars.next();
+                chars.next();
+                let mut text = String::new();
+                let kind = if ch == '-' {
+                    while let Some(&c) = chars.peek() {
+                        if c == '\n' {
+                            break;
+                        }
+                        text.push(c);
+                        chars.next();
+                    }
+                    CommentKind::Line
+                } else {
+                    // An unterminated block comment runs to the end of input.
+                    while let Some(c) = chars.next() {
+                        if c == '*' && chars.peek() == Some(&'/') {
+                            chars.next();
+                            break;
+                        }
+                        text.push(c);
+                    }
+                    CommentKind::Block
+                };
+                if options.keep_comments {
+                    tokens.push(Token::Comment(text, kind));
+                }
+            }
             '+' | '-' | '/' => {
                 let op = chars.next().unwrap();
                 tokens.push(Token::Operator(op.to_string()));
             }
-            '\'' | '"' => {
+            // A double-quoted identifier keeps its case and is never a keyword,
+            // so reserved words can name tables and columns.
+            '"' => {
+                let mut name = String::new();
+                quoted_name(&mut chars, &mut name);
+                tokens.push(Token::Identifier(interner.intern(&name)));
+            }
+            '\'' => {
                 let quote = chars.next().unwrap();
                 let mut value = String::new();
                 while let Some(&c) = chars.peek() {
@@ -92,59 +534,67 @@ pub fn tokenize(input: &str) -> Vec<Token> {
             _ => {
                 let mut ident = String::new();
                 while let Some(&c) = chars.peek() {
-                    if c.is_alphanumeric() || c == '_' || c == '.' {
+                    // `@` covers MySQL user (`@var`) and system (`@@global.var`) variables.
+                    if c.is_alphanumeric() || c == '_' || c == '.' || c == '@' {
                         ident.push(c);
                         chars.next();
                     } else {
                         break;
                     }
                 }
+                if ident.ends_with('.') && chars.peek() == Some(&'"') {
+                    // `t."col"`: a quoted part after a qualifier.
+                    quoted_name(&mut chars, &mut ident);
+                    tokens.push(Token::Identifier(interner.intern(&ident)));
+                    continue;
+                }
+                if ident.is_empty() {
+                    // A character no other arm handles: consume it so the loop always
+                    // makes progress, and let the parser reject it.
+                    chars.next();
+                    tokens.push(Token::Operator(ch.to_string()));
+                    continue;
+                }
 
-                let upper = ident.to_uppercase();
-                match upper.as_str() {
-                    "SELECT" | "FROM" | "WHERE" | "CREATE" | "TABLE" | "ORDER" | "BY" | "NOT" => {
-                        tokens.push(Token::Keyword(upper));
-                    }
-                    "AND" | "OR" => {
-                        tokens.push(Token::Operator(upper));
-                    }
-                    "TRUE" => {
-                        tokens.push(Token::BoolLiteral(true));
-                    }
-                    "FALSE" => {
-                        tokens.push(Token::BoolLiteral(false));
-                    }
-                    "ASC" => tokens.push(Token::Asc),
-                    "DESC" => tokens.push(Token::Desc),
-                    "INT" => tokens.push(Token::Int),
-                    "VARCHAR" => {
-                        chars.next(); // Consume '('
-                        let mut len_str = String::new();
-                        while let Some(&c) = chars.peek() {
-                            if c.is_digit(10) {
-                                len_str.push(c);
-                                chars.next();
+                match lookup_word(&ident) {
+                    Some(Word::Keyword(keyword)) => tokens.push(Token::Keyword(keyword)),
+                    Some(Word::Operator(op)) => tokens.push(Token::Operator(op.to_string())),
+                    Some(Word::Bool(value)) => tokens.push(Token::BoolLiteral(value)),
+                    Some(Word::Asc) => tokens.push(Token::Asc),
+                    Some(Word::Desc) => tokens.push(Token::Desc),
+                    Some(Word::Int) => tokens.push(Token::Int),
+                    Some(Word::Varchar) => {
+                        if let Some('(') = chars.peek() {
+                            chars.next(); // Consume '('
+                            let mut len_str = String::new();
+                            while let Some(&c) = chars.peek() {
+                                if c.is_ascii_digit() {
+                                    len_str.push(c);
+                                    chars.next();
+                                } else {
+                                    break;
+                                }
+                            }
+                            if let Some(')') = chars.peek() {
+                                chars.next(); // Consume ')'
+                            }
+                            if let Ok(len) = len_str.parse::<u64>() {
+                                tokens.push(Token::Varchar(Some(len)));
                             } else {
-                                break;
+                                tokens.push(Token::Identifier(interner.intern(&ident))); // Treat as identifier if parsing fails
                             }
-                        }
-                        chars.next(); // Consume ')'
-                        if let Ok(len) = len_str.parse::<u64>() {
-                            tokens.push(Token::Varchar(len));
                         } else {
-                            tokens.push(Token::Identifier(ident)); // Treat as identifier if parsing fails
+                            tokens.push(Token::Varchar(None)); // Length left to the parser's default
                         }
                     }
-                    "BOOL" => tokens.push(Token::Bool),
-                    "PRIMARY" => tokens.push(Token::PrimaryKey),
-                    "KEY" => tokens.push(Token::Keyword("KEY".to_string())), // Keep as keyword for now
-                    "NULL" => tokens.push(Token::Keyword("NULL".to_string())), // Keep as keyword for now
-                    "CHECK" => tokens.push(Token::Check),
-                    _ => {
+                    Some(Word::BoolType) => tokens.push(Token::Bool),
+                    Some(Word::PrimaryKey) => tokens.push(Token::PrimaryKey),
+                    Some(Word::Check) => tokens.push(Token::Check),
+                    None => {
                         if let Ok(num) = ident.parse::<i64>() {
                             tokens.push(Token::Number(num));
                         } else {
-                            tokens.push(Token::Identifier(ident));
+                            tokens.push(Token::Identifier(interner.intern(&ident)));
                         }
                     }
                 }
@@ -152,6 +602,65 @@ pub fn tokenize(input: &str) -> Vec<Token> {
         }
     }
 
-    tokens.push(Token::EOF);
+    tokens.push(Token::Eof);
     tokens
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn postgres_type_names_are_keywords() {
+        assert_eq!(
+            tokenize("uuid Json JSONB xml Bytea"),
+            vec![
+                Token::Keyword(Keyword::Uuid),
+                Token::Keyword(Keyword::Json),
+                Token::Keyword(Keyword::Jsonb),
+                Token::Keyword(Keyword::Xml),
+                Token::Keyword(Keyword::Bytea),
+                Token::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn repeated_identifiers_share_one_symbol() {
+        let tokens = tokenize("price, price");
+        match (&tokens[0], &tokens[2]) {
+            (Token::Identifier(first), Token::Identifier(second)) => {
+                assert_eq!(first.resolve(), "price");
+                assert!(std::ptr::eq(first.resolve(), second.resolve()));
+            }
+            other => panic!("expected two identifiers, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn comments_are_kept_with_their_kind() {
+        let options = TokenizerOptions { keep_comments: true };
+        assert_eq!(
+            tokenize_with_options("a -- trailing\n/* block\n spans */ b /* unterminated", &options),
+            vec![
+                Token::Identifier(Symbol::from("a")),
+                Token::Comment(" trailing".to_string(), CommentKind::Line),
+                Token::Comment(" block\n spans ".to_string(), CommentKind::Block),
+                Token::Identifier(Symbol::from("b")),
+                Token::Comment(" unterminated".to_string(), CommentKind::Block),
+                Token::Eof,
+            ]
+        );
+        // Dropped by default; `-` and `/` alone are still operators.
+        assert_eq!(
+            tokenize("a - b -- c\n/ d /* e */"),
+            vec![
+                Token::Identifier(Symbol::from("a")),
+                Token::Operator("-".to_string()),
+                Token::Identifier(Symbol::from("b")),
+                Token::Operator("/".to_string()),
+                Token::Identifier(Symbol::from("d")),
+                Token::Eof,
+            ]
+        );
+    }
+}